@@ -0,0 +1,138 @@
+//! Compares ball sweeps against every brick with sweeps against
+//! the bricks found by the [`BroadPhase`].
+//!
+//! Run with `cargo bench --bench broad_phase`.
+
+#![feature(test)]
+
+extern crate test;
+
+use bevy::prelude::*;
+use bevyout::game::broad_phase::BroadPhase;
+use bevyout::game::physics::ball_rect_sweep;
+use test::{black_box, Bencher};
+
+const BALL_RADIUS: f32 = 2.0;
+const BRICK_SIZE: Vec2 = Vec2::new(9.0, 4.0);
+
+struct Brick {
+    entity: Entity,
+    center: Vec2,
+    half_size: Vec2,
+}
+
+/// Dense level with `columns * rows` bricks covering the 200x300 scene
+fn bricks(columns: u32, rows: u32) -> Vec<Brick> {
+    let step = Vec2::new(200.0 / columns as f32, 300.0 / rows as f32);
+    (0..columns * rows)
+        .map(|index| Brick {
+            entity: Entity::from_raw(index),
+            center: Vec2::new(
+                (index % columns) as f32 + 0.5,
+                (index / columns) as f32 + 0.5,
+            ) * step,
+            half_size: BRICK_SIZE.min(step) / 2.0,
+        })
+        .collect()
+}
+
+/// Single fixed step movement of several balls spread across the scene
+fn ball_steps() -> Vec<(Vec2, Vec2)> {
+    (0..64)
+        .map(|index| {
+            let start = Vec2::new((index * 37 % 200) as f32, (index * 53 % 300) as f32);
+            let motion = Vec2::from_angle(index as f32 * 0.7) * 3.0;
+            (start, motion)
+        })
+        .collect()
+}
+
+fn brute_force(b: &mut Bencher, bricks: &[Brick]) {
+    let steps = ball_steps();
+    b.iter(|| {
+        let mut hits = 0;
+        for (start, motion) in steps.iter() {
+            for brick in bricks.iter() {
+                if ball_rect_sweep(*start, BALL_RADIUS, *motion, brick.center, brick.half_size)
+                    .is_some()
+                {
+                    hits += 1;
+                }
+            }
+        }
+        black_box(hits)
+    });
+}
+
+fn broad_phase(b: &mut Bencher, bricks: &[Brick]) {
+    let mut grid = BroadPhase::default();
+    for brick in bricks.iter() {
+        grid.insert(
+            brick.entity,
+            brick.center - brick.half_size,
+            brick.center + brick.half_size,
+        );
+    }
+
+    let steps = ball_steps();
+    let mut candidates = Vec::new();
+    b.iter(|| {
+        let mut hits = 0;
+        for (start, motion) in steps.iter() {
+            let radius = Vec2::splat(BALL_RADIUS);
+            grid.query(
+                start.min(*start + *motion) - radius,
+                start.max(*start + *motion) + radius,
+                &mut candidates,
+            );
+            for entity in candidates.iter() {
+                let brick = &bricks[entity.index() as usize];
+                if ball_rect_sweep(*start, BALL_RADIUS, *motion, brick.center, brick.half_size)
+                    .is_some()
+                {
+                    hits += 1;
+                }
+            }
+        }
+        black_box(hits)
+    });
+}
+
+#[bench]
+fn brute_force_50(b: &mut Bencher) {
+    brute_force(b, &bricks(10, 5));
+}
+
+#[bench]
+fn broad_phase_50(b: &mut Bencher) {
+    broad_phase(b, &bricks(10, 5));
+}
+
+#[bench]
+fn brute_force_500(b: &mut Bencher) {
+    brute_force(b, &bricks(20, 25));
+}
+
+#[bench]
+fn broad_phase_500(b: &mut Bencher) {
+    broad_phase(b, &bricks(20, 25));
+}
+
+#[bench]
+fn broad_phase_despawn_500(b: &mut Bencher) {
+    let bricks = bricks(20, 25);
+    b.iter(|| {
+        let mut grid = BroadPhase::default();
+        for brick in bricks.iter() {
+            grid.insert(
+                brick.entity,
+                brick.center - brick.half_size,
+                brick.center + brick.half_size,
+            );
+        }
+        for brick in bricks.iter() {
+            grid.remove(brick.entity);
+        }
+        black_box(grid)
+    });
+}
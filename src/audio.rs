@@ -0,0 +1,159 @@
+use bevy::asset::LoadState;
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use crate::config::{AudioConfig, GameSettings, SoundEffect};
+use crate::events::GameplayEvents;
+use crate::ui::UiState;
+
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                play_gameplay_sounds,
+                music_select.run_if(state_changed::<UiState>),
+                music_fade,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Looping music track. The volume moves towards the `target_volume` over time,
+/// track is despawned once it fades out completely.
+#[derive(Component, Debug)]
+struct MusicTrack {
+    source: Handle<AudioSource>,
+    volume: f32,
+    target_volume: f32,
+}
+
+fn play_gameplay_sounds(
+    config: Res<AudioConfig>,
+    settings: Res<GameSettings>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut gameplay_events: EventReader<GameplayEvents>,
+) {
+    for event in gameplay_events.read() {
+        let sound = match event {
+            GameplayEvents::BrickHit => &config.brick_hit,
+            GameplayEvents::BrickDestroyed => &config.brick_destroyed,
+            GameplayEvents::PlatformBounce => &config.platform_bounce,
+            GameplayEvents::WallBounce => &config.wall_bounce,
+            GameplayEvents::LifeLost => &config.life_lost,
+            GameplayEvents::LevelCleared => &config.level_cleared,
+            GameplayEvents::PowerUpCaught => &config.power_up_caught,
+        };
+        play_sound(&mut commands, &asset_server, sound, settings.sound_volume);
+    }
+}
+
+fn play_sound(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    sound: &SoundEffect,
+    volume: f32,
+) {
+    // Audio entity with a failed source is never played and never despawned
+    if let Some(LoadState::Failed(_)) = asset_server.get_load_state(&sound.source) {
+        return;
+    }
+    commands.spawn(AudioBundle {
+        source: sound.source.clone(),
+        settings: PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::new(volume * sound.volume),
+            speed: sound.speed,
+            ..default()
+        },
+    });
+}
+
+fn music_select(
+    config: Res<AudioConfig>,
+    ui_state: Res<State<UiState>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut tracks: Query<&mut MusicTrack>,
+) {
+    let (source, volume) = match ui_state.get() {
+        UiState::MainMenu | UiState::HighScores | UiState::Replays => {
+            (&config.main_menu_music, 1.0)
+        }
+        UiState::InGame | UiState::Replay => (&config.in_game_music, 1.0),
+        UiState::Paused | UiState::LevelComplete => {
+            (&config.in_game_music, config.music_ducked_volume)
+        }
+        UiState::EndGame | UiState::Victory => (&config.end_game_music, 1.0),
+        // Settings are accessible from multiple places, keep the current music
+        UiState::Settings | UiState::KeyBindings => return,
+    };
+
+    let mut playing = false;
+    for mut track in tracks.iter_mut() {
+        if track.source == *source {
+            track.target_volume = volume;
+            playing = true;
+        } else {
+            track.target_volume = 0.0;
+        }
+    }
+
+    if playing {
+        return;
+    }
+    // Missing track is silence, other tracks still fade out
+    if let Some(LoadState::Failed(_)) = asset_server.get_load_state(source) {
+        return;
+    }
+    commands
+        .spawn(AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::new(0.0),
+                ..default()
+            },
+        })
+        .insert(MusicTrack {
+            source: source.clone(),
+            volume: 0.0,
+            target_volume: volume,
+        });
+}
+
+fn music_fade(
+    time: Res<Time>,
+    config: Res<AudioConfig>,
+    settings: Res<GameSettings>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut tracks: Query<(Entity, &mut MusicTrack, Option<&AudioSink>)>,
+) {
+    let step = time.delta_seconds() / config.music_fade_duration;
+    for (entity, mut track, sink) in tracks.iter_mut() {
+        // Track selected while still loading may fail afterwards
+        if let Some(LoadState::Failed(_)) = asset_server.get_load_state(&track.source) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        track.volume = if track.volume < track.target_volume {
+            (track.volume + step).min(track.target_volume)
+        } else {
+            (track.volume - step).max(track.target_volume)
+        };
+
+        if track.volume == 0.0 && track.target_volume == 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(sink) = sink {
+            sink.set_volume(track.volume * settings.music_volume);
+        }
+    }
+}
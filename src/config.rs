@@ -1,98 +1,535 @@
+use std::path::Path;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use crate::storage::{config_dir, read_ron, write_ron};
 
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
+        app.init_asset::<GameConfig>();
+        app.init_asset_loader::<GameConfigLoader>();
         app.add_systems(
             PreStartup,
-            (setup_game_settings, setup_game_config, setup_ui_config),
+            (
+                setup_game_settings,
+                setup_game_config,
+                setup_audio_config,
+                setup_ui_config,
+            ),
+        );
+        app.add_systems(Startup, apply_display_mode);
+        app.add_systems(
+            Update,
+            (
+                game_config_update,
+                camera_update.run_if(resource_changed::<GameConfig>),
+            )
+                .chain(),
         );
     }
 }
 
-#[derive(Debug, Clone, Resource)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    FullScreen,
+}
+
+impl From<DisplayMode> for WindowMode {
+    fn from(mode: DisplayMode) -> Self {
+        match mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::FullScreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+/// Device used to move the platform and launch the ball
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputScheme {
+    /// Bound keys to move and launch, `A`/`D` and `Space` by default
+    #[default]
+    Keyboard,
+    /// Platform follows the cursor, left button to launch
+    Mouse,
+    /// Left stick or d-pad to move, south button to launch
+    Gamepad,
+}
+
+/// Amount of particles spawned by the visual effects
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParticleDensity {
+    Off,
+    Low,
+    #[default]
+    High,
+}
+
+impl ParticleDensity {
+    /// Part of the particles of an effect that are spawned
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            ParticleDensity::Off => 0.0,
+            ParticleDensity::Low => 0.4,
+            ParticleDensity::High => 1.0,
+        }
+    }
+}
+
+/// Amount of the camera motion from gameplay effects, can be reduced
+/// for players sensitive to it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraMotion {
+    Off,
+    Reduced,
+    #[default]
+    Full,
+}
+
+impl CameraMotion {
+    /// Part of the camera motion that is applied
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            CameraMotion::Off => 0.0,
+            CameraMotion::Reduced => 0.3,
+            CameraMotion::Full => 1.0,
+        }
+    }
+}
+
+/// Gameplay actions which can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Launch,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Launch,
+        Action::Pause,
+    ];
+}
+
+/// Keys bound to the [`Action`]s. Movement and launch keys are used with
+/// the [`InputScheme::Keyboard`], pause key is used with every scheme.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub launch: KeyCode,
+    pub pause: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_left: KeyCode::KeyA,
+            move_right: KeyCode::KeyD,
+            launch: KeyCode::Space,
+            pause: KeyCode::Escape,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::MoveLeft => self.move_left,
+            Action::MoveRight => self.move_right,
+            Action::Launch => self.launch,
+            Action::Pause => self.pause,
+        }
+    }
+
+    pub fn set(&mut self, action: Action, key: KeyCode) {
+        match action {
+            Action::MoveLeft => self.move_left = key,
+            Action::MoveRight => self.move_right = key,
+            Action::Launch => self.launch = key,
+            Action::Pause => self.pause = key,
+        }
+    }
+
+    /// Returns the action the key is bound to
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.key(*action) == key)
+    }
+
+    pub fn pressed(&self, keys: &ButtonInput<KeyCode>, action: Action) -> bool {
+        keys.pressed(self.key(action))
+    }
+
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>, action: Action) -> bool {
+        keys.just_pressed(self.key(action))
+    }
+}
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
+    /// Version of the settings file schema. Files written before
+    /// the version was added have none and are version 0.
+    #[serde(default)]
+    pub version: u32,
     pub sound_volume: f32,
+    pub music_volume: f32,
+    pub display_mode: DisplayMode,
+    pub input_scheme: InputScheme,
+    pub key_bindings: KeyBindings,
+    pub particle_density: ParticleDensity,
+    pub camera_motion: CameraMotion,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            version: GameSettings::VERSION,
+            sound_volume: 0.1,
+            music_volume: 0.1,
+            display_mode: DisplayMode::Windowed,
+            input_scheme: InputScheme::Keyboard,
+            key_bindings: KeyBindings::default(),
+            particle_density: ParticleDensity::default(),
+            camera_motion: CameraMotion::default(),
+        }
+    }
+}
+
+impl GameSettings {
+    /// Current version of the settings file schema. Bump it and add a step
+    /// to the [`GameSettings::migrate`] when the meaning of the fields changes.
+    /// New fields do not need a version bump, they are filled with defaults.
+    pub const VERSION: u32 = 1;
+    const FILE_NAME: &'static str = "settings.ron";
+
+    /// Loads settings from the config directory. Falls back to
+    /// defaults if the file is missing or can not be parsed.
+    pub fn load() -> Self {
+        let Some(path) = config_dir().map(|dir| dir.join(Self::FILE_NAME)) else {
+            warn!("Could not find config directory, using default settings");
+            return Self::default();
+        };
+        Self::load_from(&path)
+    }
+
+    fn load_from(path: &Path) -> Self {
+        match read_ron::<Self>(path) {
+            Ok(Some(settings)) => settings.migrate(),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                warn!("Could not load settings from {path:?}: {e}, using default settings");
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes settings into the config directory.
+    pub fn save(&self) {
+        let Some(path) = config_dir().map(|dir| dir.join(Self::FILE_NAME)) else {
+            warn!("Could not find config directory, settings are not saved");
+            return;
+        };
+        self.save_to(&path);
+    }
+
+    fn save_to(&self, path: &Path) {
+        // Would drop the fields this version does not know about
+        if Self::VERSION < self.version {
+            warn!(
+                "Settings file version {} is newer than supported version {}, settings are not saved",
+                self.version,
+                Self::VERSION
+            );
+            return;
+        }
+        if let Err(e) = write_ron(path, self) {
+            warn!("Could not save settings to {path:?}: {e}");
+        }
+    }
+
+    /// Upgrades settings read from an older file one version at a time.
+    /// Settings from a newer file keep their version, so they are never
+    /// written back by this version of the game.
+    fn migrate(mut self) -> Self {
+        if Self::VERSION < self.version {
+            warn!(
+                "Settings file version {} is newer than supported version {}",
+                self.version,
+                Self::VERSION
+            );
+            return self;
+        }
+        while self.version < Self::VERSION {
+            match self.version {
+                // Unversioned files have the same fields as the version 1
+                0 => {}
+                _ => unreachable!("no migration from settings version {}", self.version),
+            }
+            self.version += 1;
+        }
+        self
+    }
 }
 
 pub fn setup_game_settings(mut commands: Commands) {
-    let settings = GameSettings { sound_volume: 0.1 };
+    commands.insert_resource(GameSettings::load());
+}
 
-    commands.insert_resource(settings);
+fn apply_display_mode(
+    settings: Res<GameSettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if let Ok(mut main_window) = windows.get_single_mut() {
+        main_window.mode = settings.display_mode.into();
+    }
 }
 
-#[derive(Debug, Clone, Resource)]
+/// Gameplay constants. Loaded from the `assets/config/game.config.ron`
+/// and reloaded every time the file changes. Missing fields use default values.
+#[derive(Debug, Clone, Resource, Asset, TypePath, Deserialize)]
+#[serde(default)]
 pub struct GameConfig {
     pub ball_radius: f32,
     pub ball_speed: f32,
     pub ball_max_speed_multiplier: f32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
     pub ball_base_color: Color,
+    #[serde(deserialize_with = "deserialize_hex_color")]
     pub ball_max_speed_color: Color,
 
-    pub bricks_pos_x: f32,
-    pub bricks_pos_y: f32,
-    pub bricks_width: f32,
-    pub bricks_height: f32,
-    pub bricks_cols: u32,
-    pub bricks_rows: u32,
-    pub bricks_gap_x: f32,
-    pub bricks_gap_y: f32,
-    pub bricks_health: u32,
-    pub bricks_sound: Handle<AudioSource>,
-    pub bricks_color: Color,
-
     pub platform_width: f32,
     pub platform_height: f32,
     pub platform_speed: f32,
+    /// Maximum speed of the platform following the cursor
+    pub platform_mouse_max_speed: f32,
     pub platform_lifes: u32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
     pub platform_color: Color,
+    /// Angle in degrees from the vertical at which the ball leaves the platform edge
+    pub platform_max_bounce_angle: f32,
+    /// Part of the `platform_max_bounce_angle` added to the bounce when
+    /// the platform moves at full speed
+    pub platform_english: f32,
 
     pub scene_width: f32,
     pub scene_height: f32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
     pub scene_border_color: Color,
+
+    /// Number of physics steps per second
+    pub physics_tick_rate: f64,
+
+    /// Chance from 0 to 1 for a destroyed brick to drop a power-up
+    pub power_up_drop_chance: f32,
+    pub power_up_fall_speed: f32,
+    pub power_up_width: f32,
+    pub power_up_height: f32,
+    /// Time in seconds timed power-ups stay active
+    pub power_up_duration: f32,
+    /// Laser bolts fired per second while the laser power-up is active
+    pub laser_fire_rate: f32,
+    pub laser_bolt_speed: f32,
+
+    /// Brick hits in a row without touching the platform needed
+    /// to raise the score multiplier by one
+    pub combo_hits_per_multiplier: u32,
+    pub combo_max_multiplier: u32,
+    /// Part of the brick points added for each unit of the ball speed multiplier above 1
+    pub speed_bonus: f32,
+    /// Points for each life left when the level is cleared
+    pub life_bonus: u32,
 }
 
-pub fn setup_game_config(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let config = GameConfig {
-        ball_radius: 5.0,
-        ball_speed: 100.0,
-        ball_max_speed_multiplier: 2.5,
-        ball_base_color: Color::srgb_u8(0x00, 0x75, 0x5f),
-        ball_max_speed_color: Color::srgb_u8(0xdd, 0xdf, 0x00),
-
-        bricks_pos_x: 100.0,
-        bricks_pos_y: 200.0,
-        bricks_width: 15.0,
-        bricks_height: 10.0,
-        bricks_cols: 9,
-        bricks_rows: 5,
-        bricks_gap_x: 5.0,
-        bricks_gap_y: 5.0,
-        bricks_health: 1,
-        bricks_sound: asset_server.load("audio/bling.ogg"),
-        bricks_color: Color::srgb_u8(0xe8, 0x5d, 0x04),
-
-        platform_width: 50.0,
-        platform_height: 10.0,
-        platform_speed: 100.0,
-        platform_lifes: 5,
-        platform_color: Color::srgb_u8(0x6a, 0x04, 0x0f),
-
-        scene_width: 200.0,
-        scene_height: 350.0,
-        scene_border_color: Color::srgb_u8(0xfa, 0xa3, 0x07),
-    };
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            ball_radius: 5.0,
+            ball_speed: 100.0,
+            ball_max_speed_multiplier: 2.5,
+            ball_base_color: Color::srgb_u8(0x00, 0x75, 0x5f),
+            ball_max_speed_color: Color::srgb_u8(0xdd, 0xdf, 0x00),
+
+            platform_width: 50.0,
+            platform_height: 10.0,
+            platform_speed: 100.0,
+            platform_mouse_max_speed: 300.0,
+            platform_lifes: 5,
+            platform_color: Color::srgb_u8(0x6a, 0x04, 0x0f),
+            platform_max_bounce_angle: 60.0,
+            platform_english: 0.3,
+
+            scene_width: 200.0,
+            scene_height: 350.0,
+            scene_border_color: Color::srgb_u8(0xfa, 0xa3, 0x07),
+
+            physics_tick_rate: 120.0,
+
+            power_up_drop_chance: 0.15,
+            power_up_fall_speed: 40.0,
+            power_up_width: 12.0,
+            power_up_height: 5.0,
+            power_up_duration: 10.0,
+            laser_fire_rate: 2.0,
+            laser_bolt_speed: 200.0,
+
+            combo_hits_per_multiplier: 5,
+            combo_max_multiplier: 4,
+            speed_bonus: 1.0,
+            life_bonus: 10,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn scene_center(&self) -> Vec3 {
+        Vec3::new(self.scene_width / 2.0, self.scene_height / 2.0, 0.0)
+    }
+
+    /// Camera in front of the scene center, without any effects
+    pub fn camera_transform(&self) -> Transform {
+        Transform::from_translation(self.scene_center() + Vec3::Z * 500.0)
+            .looking_at(self.scene_center(), Vec3::Y)
+    }
+
+    fn validate(&self) -> Result<(), GameConfigLoaderError> {
+        let positive = [
+            ("ball_radius", self.ball_radius),
+            ("ball_speed", self.ball_speed),
+            ("platform_width", self.platform_width),
+            ("platform_height", self.platform_height),
+            ("platform_speed", self.platform_speed),
+            ("platform_mouse_max_speed", self.platform_mouse_max_speed),
+            ("scene_width", self.scene_width),
+            ("scene_height", self.scene_height),
+            ("power_up_fall_speed", self.power_up_fall_speed),
+            ("power_up_width", self.power_up_width),
+            ("power_up_height", self.power_up_height),
+            ("power_up_duration", self.power_up_duration),
+            ("laser_fire_rate", self.laser_fire_rate),
+            ("laser_bolt_speed", self.laser_bolt_speed),
+        ];
+        for (field, value) in positive {
+            if value <= 0.0 {
+                return Err(GameConfigLoaderError::NotPositive { field, value });
+            }
+        }
+        if self.ball_max_speed_multiplier < 1.0 {
+            return Err(GameConfigLoaderError::SpeedMultiplier(
+                self.ball_max_speed_multiplier,
+            ));
+        }
+        if self.platform_lifes == 0 {
+            return Err(GameConfigLoaderError::NoLifes);
+        }
+        if self.platform_max_bounce_angle <= 0.0 || 85.0 < self.platform_max_bounce_angle {
+            return Err(GameConfigLoaderError::BounceAngle(
+                self.platform_max_bounce_angle,
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.platform_english) {
+            return Err(GameConfigLoaderError::English(self.platform_english));
+        }
+        if self.physics_tick_rate <= 0.0 {
+            return Err(GameConfigLoaderError::TickRate(self.physics_tick_rate));
+        }
+        if !(0.0..=1.0).contains(&self.power_up_drop_chance) {
+            return Err(GameConfigLoaderError::DropChance(self.power_up_drop_chance));
+        }
+        if self.combo_hits_per_multiplier == 0 {
+            return Err(GameConfigLoaderError::ComboHits);
+        }
+        if self.combo_max_multiplier == 0 {
+            return Err(GameConfigLoaderError::ComboMultiplier);
+        }
+        if self.speed_bonus < 0.0 {
+            return Err(GameConfigLoaderError::SpeedBonus(self.speed_bonus));
+        }
+        Ok(())
+    }
+}
 
-    // camera
-    let cam_pos = Vec3::new(config.scene_width / 2.0, config.scene_height / 2.0, 500.0);
-    let cam_look_at = Vec3::new(config.scene_width / 2.0, config.scene_height / 2.0, 0.0);
+#[derive(Resource, Debug, Clone)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
 
-    commands.insert_resource(config);
+#[derive(Debug, Error)]
+pub enum GameConfigLoaderError {
+    #[error("could not read game config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse game config file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("{field} must be positive, got {value}")]
+    NotPositive { field: &'static str, value: f32 },
+    #[error("ball_max_speed_multiplier must be at least 1.0, got {0}")]
+    SpeedMultiplier(f32),
+    #[error("platform_lifes must be at least 1")]
+    NoLifes,
+    #[error("platform_max_bounce_angle must be between 0 and 85 degrees, got {0}")]
+    BounceAngle(f32),
+    #[error("platform_english must be between 0.0 and 1.0, got {0}")]
+    English(f32),
+    #[error("physics_tick_rate must be positive, got {0}")]
+    TickRate(f64),
+    #[error("power_up_drop_chance must be between 0.0 and 1.0, got {0}")]
+    DropChance(f32),
+    #[error("combo_hits_per_multiplier must be at least 1")]
+    ComboHits,
+    #[error("combo_max_multiplier must be at least 1")]
+    ComboMultiplier,
+    #[error("speed_bonus must not be negative, got {0}")]
+    SpeedBonus(f32),
+}
+
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = GameConfigLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let config = ron::de::from_bytes::<GameConfig>(&bytes)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
 
+pub fn setup_game_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Defaults are used until the config file is loaded
+    commands.insert_resource(GameConfig::default());
+    commands.insert_resource(GameConfigHandle(
+        asset_server.load("config/game.config.ron"),
+    ));
+
+    // camera, positioned by the `camera_update` once config is available
     commands.spawn(Camera3dBundle {
         camera: Camera { ..default() },
-        transform: Transform::from_translation(cam_pos).looking_at(cam_look_at, Vec3::Y),
         ..default()
     });
 
@@ -112,6 +549,95 @@ pub fn setup_game_config(mut commands: Commands, asset_server: Res<AssetServer>)
     });
 }
 
+fn game_config_update(
+    config_handle: Res<GameConfigHandle>,
+    configs: Res<Assets<GameConfig>>,
+    mut config: ResMut<GameConfig>,
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+) {
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id != config_handle.0.id() {
+                continue;
+            }
+            if let Some(new_config) = configs.get(*id) {
+                info!("Game config is updated");
+                *config = new_config.clone();
+            }
+        }
+    }
+}
+
+fn camera_update(config: Res<GameConfig>, mut camera: Query<&mut Transform, With<Camera3d>>) {
+    if let Ok(mut transform) = camera.get_single_mut() {
+        *transform = config.camera_transform();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SoundEffect {
+    pub source: Handle<AudioSource>,
+    pub speed: f32,
+    /// Multiplied by the [`GameSettings::sound_volume`]
+    pub volume: f32,
+}
+
+#[derive(Debug, Clone, Resource)]
+pub struct AudioConfig {
+    pub brick_hit: SoundEffect,
+    pub brick_destroyed: SoundEffect,
+    pub platform_bounce: SoundEffect,
+    pub wall_bounce: SoundEffect,
+    pub life_lost: SoundEffect,
+    pub level_cleared: SoundEffect,
+    pub power_up_caught: SoundEffect,
+
+    /// Music tracks are optional, the game is silent where one fails to load
+    pub main_menu_music: Handle<AudioSource>,
+    pub in_game_music: Handle<AudioSource>,
+    pub end_game_music: Handle<AudioSource>,
+    /// Music volume multiplier while the game is paused
+    pub music_ducked_volume: f32,
+    /// Time in seconds for music to fade in or out
+    pub music_fade_duration: f32,
+}
+
+fn setup_audio_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let bling = asset_server.load("audio/bling.ogg");
+    let sound = |speed: f32, volume: f32| SoundEffect {
+        source: bling.clone(),
+        speed,
+        volume,
+    };
+
+    commands.insert_resource(AudioConfig {
+        brick_hit: sound(1.0, 1.0),
+        brick_destroyed: sound(1.5, 1.0),
+        platform_bounce: sound(0.7, 0.8),
+        wall_bounce: sound(0.5, 0.5),
+        life_lost: sound(0.3, 1.0),
+        level_cleared: sound(2.0, 1.0),
+        power_up_caught: sound(1.2, 0.8),
+
+        main_menu_music: asset_server.load("audio/music/main_menu.ogg"),
+        in_game_music: asset_server.load("audio/music/in_game.ogg"),
+        end_game_music: asset_server.load("audio/music/end_game.ogg"),
+        music_ducked_volume: 0.3,
+        music_fade_duration: 1.5,
+    });
+}
+
+/// Deserializes a [`Color`] from a `"#rrggbb"` hex string.
+pub fn deserialize_hex_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hex = String::deserialize(deserializer)?;
+    Srgba::hex(&hex)
+        .map(Color::from)
+        .map_err(|e| D::Error::custom(format!("invalid color \"{hex}\": {e}")))
+}
+
 #[derive(Debug, Clone, Resource)]
 pub struct UiConfig {
     pub btn_style: Style,
@@ -155,3 +681,73 @@ fn setup_ui_config(mut commands: Commands, asset_server: Res<AssetServer>) {
         },
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Settings file in a temporary directory, removed when dropped
+    struct TempSettings(PathBuf);
+
+    impl TempSettings {
+        fn new(name: &str, content: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("bevyout-settings-{}-{name}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(GameSettings::FILE_NAME);
+            std::fs::write(&path, content).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempSettings {
+        fn drop(&mut self) {
+            if let Some(dir) = self.0.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    #[test]
+    fn load_unversioned_file_migrates() {
+        let file = TempSettings::new(
+            "unversioned",
+            "(sound_volume: 0.5, music_volume: 0.3, display_mode: FullScreen)",
+        );
+        let settings = GameSettings::load_from(&file.0);
+        assert_eq!(settings.version, GameSettings::VERSION);
+        assert_eq!(settings.sound_volume, 0.5);
+        assert_eq!(settings.music_volume, 0.3);
+        assert_eq!(settings.display_mode, DisplayMode::FullScreen);
+        // Fields the file does not have are defaults
+        assert_eq!(settings.camera_motion, CameraMotion::default());
+    }
+
+    #[test]
+    fn load_corrupt_file_uses_defaults() {
+        let file = TempSettings::new("corrupt", "(sound_volume: 0.5, music_vol");
+        let settings = GameSettings::load_from(&file.0);
+        let default = GameSettings::default();
+        assert_eq!(settings.version, GameSettings::VERSION);
+        assert_eq!(settings.sound_volume, default.sound_volume);
+        assert_eq!(settings.music_volume, default.music_volume);
+    }
+
+    #[test]
+    fn newer_file_is_not_overwritten() {
+        let content = format!(
+            "(version: {}, sound_volume: 0.5, future_field: true)",
+            GameSettings::VERSION + 1
+        );
+        let file = TempSettings::new("newer", &content);
+        let mut settings = GameSettings::load_from(&file.0);
+        assert_eq!(settings.version, GameSettings::VERSION + 1);
+        assert_eq!(settings.sound_volume, 0.5);
+
+        settings.sound_volume = 0.2;
+        settings.save_to(&file.0);
+        assert_eq!(std::fs::read_to_string(&file.0).unwrap(), content);
+    }
+}
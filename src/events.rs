@@ -1,14 +1,34 @@
 use bevy::prelude::*;
-use bevy::window::{PrimaryWindow, WindowMode};
+use bevy::window::{PrimaryWindow, WindowFocused};
 
-use crate::config::GameSettings;
-use crate::game::GameState;
+use crate::config::{
+    Action, CameraMotion, DisplayMode, GameSettings, InputScheme, ParticleDensity,
+};
+use crate::game::level::{Campaign, CampaignHandle, CurrentLevel};
+use crate::game::replay::ReplayPlayback;
+use crate::game::{GameState, InLevel};
 use crate::ui::UiState;
 
 #[derive(Event)]
 pub enum GameEvents {
     EndGame,
+    LevelComplete,
+    /// Level could not be played, the game goes back to the main menu
+    LevelFailed,
     Pause,
+    Resume,
+}
+
+/// Notable moments of the gameplay other systems can react to
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameplayEvents {
+    BrickHit,
+    BrickDestroyed,
+    PlatformBounce,
+    WallBounce,
+    LifeLost,
+    LevelCleared,
+    PowerUpCaught,
 }
 
 #[derive(Event)]
@@ -17,6 +37,15 @@ pub enum SettingsEvents {
     DisplayWindowed,
     VolumeUp,
     VolumeDown,
+    MusicVolumeUp,
+    MusicVolumeDown,
+    InputKeyboard,
+    InputMouse,
+    InputGamepad,
+    /// Binds the key to the action. The key must not be used by other actions.
+    Rebind(Action, KeyCode),
+    ParticleDensity(ParticleDensity),
+    CameraMotion(CameraMotion),
 }
 
 pub struct EventsPlugin;
@@ -24,40 +53,99 @@ pub struct EventsPlugin;
 impl Plugin for EventsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<GameEvents>();
+        app.add_event::<GameplayEvents>();
         app.add_event::<SettingsEvents>();
         app.add_systems(
             Update,
-            (keyboard_input, handle_game_events).run_if(in_state(GameState::InGame)),
+            (
+                // Replays have their own controls
+                (keyboard_input, window_focus).run_if(not(resource_exists::<ReplayPlayback>)),
+                handle_game_events,
+            )
+                .chain()
+                .run_if(in_state(InLevel)),
         );
         app.add_systems(
             Update,
-            handle_settings_events.run_if(in_state(UiState::Settings)),
+            handle_settings_events
+                .run_if(in_state(UiState::Settings).or_else(in_state(UiState::KeyBindings))),
         );
     }
 }
 
-fn keyboard_input(keys: Res<ButtonInput<KeyCode>>, mut game_events: EventWriter<GameEvents>) {
-    if keys.pressed(KeyCode::Escape) {
-        game_events.send(GameEvents::Pause);
+/// Toggles the pause with the pause key or the gamepad start button
+fn keyboard_input(
+    settings: Res<GameSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    game_state: Res<State<GameState>>,
+    ui_state: Res<State<UiState>>,
+    mut game_events: EventWriter<GameEvents>,
+) {
+    let start_pressed = settings.input_scheme == InputScheme::Gamepad
+        && gamepads.iter().any(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start))
+        });
+    if !settings.key_bindings.just_pressed(&keys, Action::Pause) && !start_pressed {
+        return;
+    }
+    match (game_state.get(), ui_state.get()) {
+        (GameState::InGame, _) => {
+            game_events.send(GameEvents::Pause);
+        }
+        // Settings opened from the pause menu are left with their own buttons
+        (GameState::Paused, UiState::Paused) => {
+            game_events.send(GameEvents::Resume);
+        }
+        _ => {}
+    }
+}
+
+/// Pauses the game when the window loses focus
+fn window_focus(
+    game_state: Res<State<GameState>>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut game_events: EventWriter<GameEvents>,
+) {
+    for event in focus_events.read() {
+        if !event.focused && *game_state.get() == GameState::InGame {
+            game_events.send(GameEvents::Pause);
+        }
     }
 }
 
 fn handle_game_events(
+    campaign: Res<CampaignHandle>,
+    campaigns: Res<Assets<Campaign>>,
+    current_level: Res<CurrentLevel>,
+    playback: Option<Res<ReplayPlayback>>,
     mut ui_state: ResMut<NextState<UiState>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut game_events: EventReader<GameEvents>,
 ) {
     for event in game_events.read() {
-        match event {
-            GameEvents::EndGame => {
-                ui_state.set(UiState::EndGame);
-                game_state.set(GameState::EndGame);
-            }
-            GameEvents::Pause => {
-                ui_state.set(UiState::Paused);
-                game_state.set(GameState::Paused);
+        let (ui, game) = match event {
+            GameEvents::EndGame => (UiState::EndGame, GameState::EndGame),
+            GameEvents::LevelComplete => {
+                let last = campaigns
+                    .get(&campaign.0)
+                    .is_none_or(|campaign| current_level.is_last(campaign));
+                if last {
+                    (UiState::Victory, GameState::EndGame)
+                } else {
+                    (UiState::LevelComplete, GameState::LevelComplete)
+                }
             }
+            GameEvents::LevelFailed => (UiState::MainMenu, GameState::NotInGame),
+            GameEvents::Pause => (UiState::Paused, GameState::Paused),
+            GameEvents::Resume => (UiState::InGame, GameState::InGame),
+        };
+        // Replay screen stays over the whole playback, unless it can not go on
+        if playback.is_none() || matches!(event, GameEvents::LevelFailed) {
+            ui_state.set(ui);
         }
+        game_state.set(game);
     }
 }
 
@@ -69,13 +157,18 @@ fn handle_settings_events(
     let Ok(mut main_window) = windows.get_single_mut() else {
         return;
     };
+    if settings_events.is_empty() {
+        return;
+    }
     for event in settings_events.read() {
         match event {
             SettingsEvents::DisplayWindowed => {
-                main_window.mode = WindowMode::Windowed;
+                settings.display_mode = DisplayMode::Windowed;
+                main_window.mode = settings.display_mode.into();
             }
             SettingsEvents::DisplayFullScreen => {
-                main_window.mode = WindowMode::Fullscreen;
+                settings.display_mode = DisplayMode::FullScreen;
+                main_window.mode = settings.display_mode.into();
             }
             SettingsEvents::VolumeUp => {
                 settings.sound_volume += 0.01;
@@ -85,6 +178,33 @@ fn handle_settings_events(
                 settings.sound_volume -= 0.01;
                 settings.sound_volume = settings.sound_volume.clamp(0.0, 1.0);
             }
+            SettingsEvents::MusicVolumeUp => {
+                settings.music_volume += 0.01;
+                settings.music_volume = settings.music_volume.clamp(0.0, 1.0);
+            }
+            SettingsEvents::MusicVolumeDown => {
+                settings.music_volume -= 0.01;
+                settings.music_volume = settings.music_volume.clamp(0.0, 1.0);
+            }
+            SettingsEvents::InputKeyboard => {
+                settings.input_scheme = InputScheme::Keyboard;
+            }
+            SettingsEvents::InputMouse => {
+                settings.input_scheme = InputScheme::Mouse;
+            }
+            SettingsEvents::InputGamepad => {
+                settings.input_scheme = InputScheme::Gamepad;
+            }
+            SettingsEvents::Rebind(action, key) => {
+                settings.key_bindings.set(*action, *key);
+            }
+            SettingsEvents::ParticleDensity(density) => {
+                settings.particle_density = *density;
+            }
+            SettingsEvents::CameraMotion(motion) => {
+                settings.camera_motion = *motion;
+            }
         }
     }
+    settings.save();
 }
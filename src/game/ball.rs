@@ -1,30 +1,38 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::config::GameConfig;
-use crate::game::physics::{Ball, CollisionEvent, Dynamic, PhysicsSet};
-use crate::game::GameState;
+use crate::events::GameplayEvents;
+use crate::game::input::{PlayerInput, PlayerInputSet};
+use crate::game::physics::{Ball, CollisionEvent, Dynamic, Interpolated, PhysicsSet};
+use crate::game::snapshot::RestoredRun;
+use crate::game::{GameState, InLevel};
 
 use crate::game::platform::GamePlatform;
-use crate::ui::cursor::WorldCursor;
+use crate::game::scene::SceneBorder;
 
 pub struct BallPlugin;
 
 impl Plugin for BallPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<GameBallEvent>();
-        app.add_systems(OnEnter(GameState::InGame), ball_spawn);
+        app.add_systems(OnEnter(InLevel), ball_spawn);
         app.add_systems(
-            Update,
-            (ball_controlls, ball_event_handler).run_if(in_state(GameState::InGame)),
+            FixedUpdate,
+            (ball_launch, ball_event_handler)
+                .chain()
+                .after(PlayerInputSet)
+                .before(PhysicsSet::Movement)
+                .run_if(in_state(GameState::InGame)),
         );
         app.add_systems(
-            Update,
+            FixedUpdate,
             ball_movement
                 .in_set(PhysicsSet::Movement)
                 .run_if(in_state(GameState::InGame)),
         );
         app.add_systems(
-            Update,
+            FixedUpdate,
             ball_collision
                 .in_set(PhysicsSet::CollisionResolution)
                 .run_if(in_state(GameState::InGame)),
@@ -32,12 +40,19 @@ impl Plugin for BallPlugin {
     }
 }
 
-#[derive(Event, Debug, Default)]
+/// Requests to the ball systems. Can be sent by any system to add balls at runtime.
+#[derive(Event, Debug, Clone, Copy)]
 pub enum GameBallEvent {
-    #[default]
-    ChangeState,
+    /// Launches all balls attached to the platform towards the `target`,
+    /// or straight up without one
+    Launch { target: Option<Vec2> },
+    /// Spawns a new ball attached to the platform
+    SpawnAttached,
+    /// Spawns a new ball moving freely from the `position` in the `direction`
+    Spawn { position: Vec2, direction: Vec2 },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameBallState {
     // Attached to the platform
     Attached,
@@ -52,69 +67,104 @@ pub struct GameBall {
     pub speed: f32,
     pub speed_mul: f32,
     pub state: GameBallState,
-    pub material: Handle<StandardMaterial>,
+    /// Horizontal offset from the platform center while attached
+    pub attach_offset: f32,
+}
+
+/// State of a ball saved with the run, also describes a ball to spawn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BallSnapshot {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub speed_mul: f32,
+    pub state: GameBallState,
+    pub attach_offset: f32,
+}
+
+impl BallSnapshot {
+    /// New ball at the base speed
+    pub fn new(position: Vec2, velocity: Vec2, state: GameBallState) -> Self {
+        Self {
+            position,
+            velocity,
+            speed_mul: 1.0,
+            state,
+            attach_offset: 0.0,
+        }
+    }
 }
 
 fn ball_spawn(
     config: Res<GameConfig>,
+    restored: Option<Res<RestoredRun>>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut ball_events: EventWriter<GameBallEvent>,
 ) {
-    let material = materials.add(StandardMaterial {
-        emissive: config.ball_base_color.into(),
-        ..default()
-    });
+    match restored {
+        Some(restored) if !restored.0.balls.is_empty() => {
+            for ball in restored.0.balls.iter() {
+                spawn_ball(&config, &mut commands, ball.clone());
+            }
+        }
+        _ => {
+            ball_events.send(GameBallEvent::SpawnAttached);
+        }
+    }
+}
+
+/// Goes from 0 at the base speed to 1 at the max speed of the ball
+pub fn ball_speed_mix(config: &GameConfig, speed_mul: f32) -> f32 {
+    // Ball which never speeds up stays at the base
+    if config.ball_max_speed_multiplier <= 1.0 {
+        return 0.0;
+    }
+    (speed_mul - 1.0) / (config.ball_max_speed_multiplier - 1.0)
+}
+
+/// Color of the ball goes from the base to the max speed one as the ball speeds up
+pub fn ball_color(config: &GameConfig, speed_mul: f32) -> Color {
+    config.ball_base_color.mix(
+        &config.ball_max_speed_color,
+        ball_speed_mix(config, speed_mul),
+    )
+}
+
+fn spawn_ball(config: &GameConfig, commands: &mut Commands, ball: BallSnapshot) {
+    let transform = Transform::from_translation(ball.position.extend(0.0));
     commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Mesh::from(Sphere {
-                radius: config.ball_radius,
-            })),
-            material: material.clone(),
-            transform: Transform::from_xyz(100.0, 50.0, 0.0),
-            ..default()
-        })
-        .insert(PointLightBundle {
-            point_light: PointLight {
-                color: config.ball_base_color,
-                intensity: 1000.0,
-                range: 1000.0,
-                radius: 1000.0,
-                ..default()
-            },
-            ..default()
-        })
-        .insert(StateScoped(GameState::InGame))
+        .spawn(TransformBundle::from_transform(transform))
+        .insert(StateScoped(InLevel))
         .insert(Ball {
             radius: config.ball_radius,
         })
         .insert(Dynamic)
+        .insert(Interpolated::new(transform.translation))
         .insert(GameBall {
-            velocity: Vec2::default(),
+            velocity: ball.velocity,
             radius: config.ball_radius,
             speed: config.ball_speed,
-            speed_mul: 1.0,
-            state: GameBallState::Attached,
-            material,
+            speed_mul: ball.speed_mul,
+            state: ball.state,
+            attach_offset: ball.attach_offset,
         });
 }
 
-fn ball_controlls(keys: Res<ButtonInput<KeyCode>>, mut ball_events: EventWriter<GameBallEvent>) {
-    if keys.just_pressed(KeyCode::Space) {
-        ball_events.send(GameBallEvent::ChangeState);
+fn ball_launch(input: Res<PlayerInput>, mut ball_events: EventWriter<GameBallEvent>) {
+    if input.launch {
+        ball_events.send(GameBallEvent::Launch { target: input.aim });
     }
 }
 
 fn ball_movement(
     time: Res<Time>,
     platform: Query<(&Transform, &GamePlatform), Without<GameBall>>,
-    mut ball: Query<(&GameBall, &mut Transform), Without<GamePlatform>>,
+    mut balls: Query<(&GameBall, &mut Transform), Without<GamePlatform>>,
 ) {
-    if let Ok((ball, mut transform)) = ball.get_single_mut() {
+    for (ball, mut transform) in balls.iter_mut() {
         match ball.state {
             GameBallState::Attached => {
                 if let Ok((platform_transform, platform)) = platform.get_single() {
-                    transform.translation.x = platform_transform.translation.x;
+                    transform.translation.x = platform_transform.translation.x + ball.attach_offset;
                     transform.translation.y =
                         platform_transform.translation.y + platform.height * 0.5 + ball.radius;
                 }
@@ -132,78 +182,122 @@ fn ball_movement(
 fn ball_collision(
     config: Res<GameConfig>,
     mut collision_events: EventReader<CollisionEvent>,
-    mut ball: Query<
-        (
-            Entity,
-            &Ball,
-            &mut GameBall,
-            &mut PointLight,
-            &mut Transform,
-        ),
-        With<Dynamic>,
-    >,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut gameplay_events: EventWriter<GameplayEvents>,
+    platforms: Query<(&GamePlatform, &Transform)>,
+    borders: Query<(), With<SceneBorder>>,
+    mut balls: Query<&mut GameBall, With<Dynamic>>,
 ) {
-    if let Ok((ball_entity, ball, mut game_ball, mut point_light, mut transform)) =
-        ball.get_single_mut()
-    {
-        for event in collision_events.read() {
-            if ball_entity == event.entity1 {
-                let offset = Vec2::new(
-                    transform.translation.x - event.collision_point.x,
-                    transform.translation.y - event.collision_point.y,
-                );
-                let distance = offset.length();
-                let normal = Vec2::new(offset.x / distance, offset.y / distance);
-                let diff = ball.radius - distance;
-                transform.translation.x += diff * normal.x;
-                transform.translation.y += diff * normal.y;
-
-                let new_vel = -2.0 * game_ball.velocity.dot(normal) * normal + game_ball.velocity;
-                game_ball.velocity = new_vel.normalize();
-
-                game_ball.speed_mul =
-                    (game_ball.speed_mul + 0.1).min(config.ball_max_speed_multiplier);
-
-                let mix = (game_ball.speed_mul - 1.0) / (config.ball_max_speed_multiplier - 1.0);
-                let new_color = config
-                    .ball_base_color
-                    .mix(&config.ball_max_speed_color, mix);
-                let material = materials.get_mut(&game_ball.material).unwrap();
-                material.emissive = new_color.into();
-                point_light.color = new_color;
+    for event in collision_events.read() {
+        let Ok(mut game_ball) = balls.get_mut(event.entity1) else {
+            continue;
+        };
+
+        let platform = platforms.get(event.entity2).ok();
+        if let Some((platform, platform_transform)) = platform {
+            gameplay_events.send(GameplayEvents::PlatformBounce);
+            if platform.sticky && game_ball.state == GameBallState::Detached {
+                game_ball.state = GameBallState::Attached;
+                game_ball.velocity = Vec2::ZERO;
+                // Hits of the sides attach the ball at the nearest end of the platform
+                let half_width = platform.width / 2.0;
+                game_ball.attach_offset = (event.collision_point.x
+                    - platform_transform.translation.x)
+                    .clamp(-half_width, half_width);
+                continue;
             }
+        } else if borders.contains(event.entity2) {
+            gameplay_events.send(GameplayEvents::WallBounce);
         }
+
+        // Physics already moved the ball to the contact, only the velocity is updated
+        if 0.0 <= game_ball.velocity.dot(event.normal) {
+            continue;
+        }
+        game_ball.velocity = match platform {
+            // Hits of the top side are steered by the player. The platform is
+            // a paddle, so the ball stopped right at the contact point.
+            Some((platform, platform_transform)) if 0.0 < event.normal.y => platform_deflection(
+                &config,
+                platform,
+                event.collision_point.x - platform_transform.translation.x,
+            ),
+            _ => {
+                let new_vel =
+                    -2.0 * game_ball.velocity.dot(event.normal) * event.normal + game_ball.velocity;
+                new_vel.normalize()
+            }
+        };
+
+        game_ball.speed_mul = (game_ball.speed_mul + 0.1).min(config.ball_max_speed_multiplier);
     }
 }
 
+/// Direction of the ball after hitting the top of the platform. The angle depends on
+/// the hit `offset` from the platform center and on the platform velocity.
+fn platform_deflection(config: &GameConfig, platform: &GamePlatform, offset: f32) -> Vec2 {
+    let max_angle = config.platform_max_bounce_angle.to_radians();
+    let hit = (offset / (platform.width / 2.0)).clamp(-1.0, 1.0);
+    // Platform speed comes from the config, which may set it to 0
+    let english = if 0.0 < platform.speed {
+        platform.velocity / platform.speed * config.platform_english
+    } else {
+        0.0
+    };
+    let angle = ((hit + english) * max_angle).clamp(-max_angle, max_angle);
+    Vec2::new(angle.sin(), angle.cos())
+}
+
 fn ball_event_handler(
     config: Res<GameConfig>,
-    cursor: Res<WorldCursor>,
+    mut commands: Commands,
     mut ball_events: EventReader<GameBallEvent>,
-    mut ball: Query<(&Transform, &mut GameBall, &mut PointLight)>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    platform: Query<(&Transform, &GamePlatform), Without<GameBall>>,
+    mut balls: Query<(&Transform, &mut GameBall)>,
 ) {
-    if let Ok((transform, mut ball, mut point_light)) = ball.get_single_mut() {
-        for _event in ball_events.read() {
-            match ball.state {
-                GameBallState::Attached => {
-                    ball.state = GameBallState::Detached;
-                    ball.velocity.x = cursor.0.x - transform.translation.x;
-                    ball.velocity.y = cursor.0.y - transform.translation.y;
-                    ball.velocity = ball.velocity.normalize();
-                    ball.speed = config.ball_speed;
-                }
-                GameBallState::Detached => {
-                    ball.state = GameBallState::Attached;
-                    ball.velocity = Vec2::ZERO;
-                    ball.speed_mul = 1.0;
-
-                    let material = materials.get_mut(&ball.material).unwrap();
-                    material.emissive = config.ball_base_color.into();
-                    point_light.color = config.ball_base_color;
+    for event in ball_events.read() {
+        match *event {
+            GameBallEvent::Launch { target } => {
+                for (transform, mut ball) in balls.iter_mut() {
+                    if ball.state == GameBallState::Attached {
+                        ball.state = GameBallState::Detached;
+                        ball.velocity = target
+                            .and_then(|target| {
+                                (target - transform.translation.truncate()).try_normalize()
+                            })
+                            .unwrap_or(Vec2::Y);
+                        ball.speed = config.ball_speed;
+                    }
                 }
             }
+            GameBallEvent::SpawnAttached => {
+                // Position is corrected by the `ball_movement` on the next step
+                let position = platform
+                    .get_single()
+                    .map(|(transform, platform)| {
+                        transform.translation.truncate()
+                            + Vec2::Y * (platform.height * 0.5 + config.ball_radius)
+                    })
+                    .unwrap_or_default();
+                spawn_ball(
+                    &config,
+                    &mut commands,
+                    BallSnapshot::new(position, Vec2::ZERO, GameBallState::Attached),
+                );
+            }
+            GameBallEvent::Spawn {
+                position,
+                direction,
+            } => {
+                spawn_ball(
+                    &config,
+                    &mut commands,
+                    BallSnapshot::new(
+                        position,
+                        direction.try_normalize().unwrap_or(Vec2::Y),
+                        GameBallState::Detached,
+                    ),
+                );
+            }
         }
     }
 }
@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use bevy::transform::TransformSystem;
+
+use crate::config::GameConfig;
+use crate::game::ball::{ball_color, ball_speed_mix, GameBall, GameBallState};
+use crate::game::physics::interpolation_apply;
+
+/// Time in seconds a point of the trail lives at the base and at the max ball speed
+const TRAIL_MIN_LIFETIME: f32 = 0.05;
+const TRAIL_MAX_LIFETIME: f32 = 0.25;
+/// Opacity of the trail right behind the ball
+const TRAIL_ALPHA: f32 = 0.6;
+/// Points above it are dropped even if still alive
+const TRAIL_MAX_POINTS: usize = 128;
+
+/// Fading ribbon behind each ball, which gets longer as the ball speeds up
+pub struct BallTrailPlugin;
+
+impl Plugin for BallTrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrailMaterial>();
+        app.add_systems(Update, trail_spawn);
+        // Follows the interpolated ball, not its simulated translation
+        app.add_systems(
+            PostUpdate,
+            trail_update
+                .after(interpolation_apply)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Trail is a child of the ball, so it is despawned together with the ball.
+/// Its mesh is rebuilt every frame from the past ball positions.
+#[derive(Component, Debug, Default)]
+struct BallTrail {
+    /// Ball positions with their age, the newest first
+    points: VecDeque<(Vec3, f32)>,
+}
+
+/// All trails share the same material and are colored by their vertices
+#[derive(Resource, Debug)]
+struct TrailMaterial(Handle<StandardMaterial>);
+
+impl FromWorld for TrailMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        TrailMaterial(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }))
+    }
+}
+
+fn trail_spawn(
+    trail_material: Res<TrailMaterial>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    balls: Query<Entity, Added<GameBall>>,
+) {
+    for entity in balls.iter() {
+        // Starts as a ribbon of no size, so the mesh is never empty
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        let points = VecDeque::from([(Vec3::ZERO, 0.0); 2]);
+        build_ribbon(&mut mesh, &points, Vec3::ZERO, 0.0, 1.0, Color::NONE);
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn(PbrBundle {
+                    mesh: meshes.add(mesh),
+                    material: trail_material.0.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                })
+                // Bounds of the mesh change every frame
+                .insert(NoFrustumCulling)
+                .insert(BallTrail::default());
+        });
+    }
+}
+
+fn trail_update(
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    balls: Query<(&GameBall, &Transform)>,
+    mut trails: Query<(&mut BallTrail, &Parent, &Handle<Mesh>, &mut Visibility)>,
+) {
+    let dt = time.delta_seconds();
+    for (mut trail, parent, mesh, mut visibility) in trails.iter_mut() {
+        let Ok((ball, transform)) = balls.get(parent.get()) else {
+            continue;
+        };
+
+        // Attached ball starts a new trail once launched again
+        if ball.state == GameBallState::Attached {
+            trail.points.clear();
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        let mix = ball_speed_mix(&config, ball.speed_mul);
+        let lifetime = TRAIL_MIN_LIFETIME.lerp(TRAIL_MAX_LIFETIME, mix);
+        // Nothing moves while the game is paused
+        if 0.0 < dt {
+            for (_, age) in trail.points.iter_mut() {
+                *age += dt;
+            }
+            trail.points.push_front((transform.translation, 0.0));
+        }
+        trail.points.retain(|(_, age)| *age < lifetime);
+        trail.points.truncate(TRAIL_MAX_POINTS);
+
+        let Some(mesh) = meshes.get_mut(mesh) else {
+            continue;
+        };
+        if trail.points.len() < 2 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        build_ribbon(
+            mesh,
+            &trail.points,
+            transform.translation,
+            ball.radius,
+            lifetime,
+            ball_color(&config, ball.speed_mul),
+        );
+    }
+}
+
+/// Ribbon through the `points` as wide as the ball, which narrows and
+/// fades out with the age of the points. Vertices are relative to the ball.
+fn build_ribbon(
+    mesh: &mut Mesh,
+    points: &VecDeque<(Vec3, f32)>,
+    origin: Vec3,
+    radius: f32,
+    lifetime: f32,
+    color: Color,
+) {
+    let color = color.to_linear();
+    let mut positions = Vec::with_capacity(points.len() * 2);
+    let mut colors = Vec::with_capacity(points.len() * 2);
+    let mut normals = Vec::with_capacity(points.len() * 2);
+    for i in 0..points.len() {
+        let (position, age) = points[i];
+        let newer = points[i.saturating_sub(1)].0;
+        let older = points[(i + 1).min(points.len() - 1)].0;
+        let tangent = (newer - older).truncate().normalize_or_zero();
+        let side = tangent.perp().extend(0.0);
+
+        let life = 1.0 - age / lifetime;
+        let half_width = radius * life;
+        let local = position - origin;
+        positions.push((local + side * half_width).to_array());
+        positions.push((local - side * half_width).to_array());
+        let vertex_color = color.with_alpha(TRAIL_ALPHA * life).to_f32_array();
+        colors.extend([vertex_color, vertex_color]);
+        normals.extend([[0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+    }
+
+    let mut indices = Vec::with_capacity((points.len() - 1) * 6);
+    for i in 0..points.len() as u32 - 1 {
+        let (a, b, c, d) = (i * 2, i * 2 + 1, i * 2 + 2, i * 2 + 3);
+        indices.extend([a, b, c, b, d, c]);
+    }
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+}
@@ -1,18 +1,31 @@
-use bevy::audio::{PlaybackMode, Volume};
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::config::{GameConfig, GameSettings};
-use crate::events::GameEvents;
-use crate::game::physics::{CollisionEvent, PhysicsSet, Rectangle};
-use crate::game::GameState;
+use crate::config::GameConfig;
+use crate::events::{GameEvents, GameplayEvents};
+use crate::game::ball::GameBall;
+use crate::game::level::{BrickKind, CampaignLevels, CurrentLevel, LevelError};
+use crate::game::physics::{CollisionEvent, Interpolated, PhysicsSet, Rectangle};
+use crate::game::snapshot::RestoredRun;
+use crate::game::{GameState, InLevel};
 
 pub struct BricksPlugin;
 
 impl Plugin for BricksPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), bricks_spawn);
+        app.add_event::<BrickDestroyedEvent>();
+        app.add_event::<BrickHitEvent>();
+        app.add_systems(OnEnter(InLevel), bricks_spawn);
+        app.add_systems(
+            FixedUpdate,
+            bricks_movement
+                .in_set(PhysicsSet::Movement)
+                .run_if(in_state(GameState::InGame)),
+        );
         app.add_systems(
-            Update,
+            FixedUpdate,
             bricks_collision
                 .in_set(PhysicsSet::CollisionResolution)
                 .run_if(in_state(GameState::InGame)),
@@ -20,136 +33,268 @@ impl Plugin for BricksPlugin {
     }
 }
 
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct GameBrick {
     health: u32,
+    max_health: u32,
+    color: Color,
+    kind: BrickKind,
+}
+
+impl GameBrick {
+    pub fn kind(&self) -> &BrickKind {
+        &self.kind
+    }
+
+    pub fn health(&self) -> u32 {
+        self.health
+    }
+
+    pub fn max_health(&self) -> u32 {
+        self.max_health
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+}
+
+/// Points the moving brick goes through in a loop
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct BrickPath {
+    points: Vec<Vec2>,
+    speed: f32,
+    next: usize,
 }
 
-#[derive(Resource, Debug, Clone)]
+#[derive(Event, Debug)]
+pub struct BrickDestroyedEvent {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub color: Color,
+    /// Speed multiplier of the ball that destroyed the brick
+    pub speed_mul: f32,
+}
+
+/// Brick lost health from a hit
+#[derive(Event, Debug)]
+pub struct BrickHitEvent {
+    pub brick: Entity,
+    pub position: Vec2,
+    /// Base points of the brick kind
+    pub points: u32,
+    /// Speed multiplier of the ball that caused the hit
+    pub speed_mul: f32,
+}
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct BricksCount {
     pub total: u32,
     pub current: u32,
 }
 
-#[derive(Resource, Debug, Clone)]
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct Score {
     pub score: u32,
 }
 
+/// State of a brick saved with the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrickSnapshot {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub brick: GameBrick,
+    pub path: Option<BrickPath>,
+}
+
+fn spawn_brick(commands: &mut Commands, snapshot: BrickSnapshot) {
+    let BrickSnapshot {
+        position,
+        size,
+        brick,
+        path,
+    } = snapshot;
+    let transform = Transform::from_translation(position.extend(0.0));
+    let mut entity = commands.spawn(TransformBundle::from_transform(transform));
+    entity
+        .insert(StateScoped(InLevel))
+        .insert(Rectangle {
+            width: size.x,
+            height: size.y,
+        })
+        .insert(brick);
+    if let Some(path) = path {
+        entity
+            .insert(Interpolated::new(transform.translation))
+            .insert(path);
+    }
+}
+
 fn bricks_spawn(
     config: Res<GameConfig>,
+    current_level: Res<CurrentLevel>,
+    campaign_levels: CampaignLevels,
+    restored: Option<Res<RestoredRun>>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut game_events: EventWriter<GameEvents>,
 ) {
-    commands.insert_resource(Score { score: 0 });
-    let total_bricks = config.bricks_cols * config.bricks_rows;
-    commands.insert_resource(BricksCount {
-        total: total_bricks,
-        current: total_bricks,
-    });
+    // Continued run has its own bricks, some of them already damaged or moved
+    if let Some(restored) = restored {
+        for brick in restored.0.bricks.iter() {
+            spawn_brick(&mut commands, brick.clone());
+        }
+        commands.insert_resource(restored.0.bricks_count.clone());
+        return;
+    }
 
-    let brick_mesh = meshes.add(Mesh::from(Cuboid::new(
-        config.bricks_width,
-        config.bricks_height,
-        1.0,
-    )));
+    let Some(level) = campaign_levels.level(current_level.index) else {
+        // Level without bricks would be cleared right away, the run is left instead
+        let message = format!("Level #{} could not be loaded", current_level.index + 1);
+        error!("{}", message);
+        commands.insert_resource(LevelError(message));
+        commands.insert_resource(BricksCount {
+            total: 0,
+            current: 0,
+        });
+        game_events.send(GameEvents::LevelFailed);
+        return;
+    };
 
-    let brick_material = materials.add(StandardMaterial {
-        emissive: config.bricks_color.into(),
-        ..default()
-    });
+    let mut total_bricks = 0;
+    for brick in level.bricks.iter() {
+        let half_size = brick.size / 2.0;
+        if brick.position.x - half_size.x < 0.0
+            || config.scene_width < brick.position.x + half_size.x
+            || brick.position.y - half_size.y < 0.0
+            || config.scene_height < brick.position.y + half_size.y
+        {
+            error!(
+                "Level \"{}\": brick at {} is outside of the scene, skipping it",
+                level.name, brick.position
+            );
+            continue;
+        }
 
-    for pos in spawn_grid(
-        Vec2::new(config.bricks_pos_x, config.bricks_pos_y),
-        config.bricks_rows,
-        config.bricks_cols,
-        config.bricks_width,
-        config.bricks_height,
-        config.bricks_gap_x,
-        config.bricks_gap_y,
-    ) {
-        commands
-            .spawn(PbrBundle {
-                mesh: brick_mesh.clone(),
-                material: brick_material.clone(),
-                transform: Transform::from_translation(pos),
-                ..default()
-            })
-            .insert(StateScoped(GameState::InGame))
-            .insert(Rectangle {
-                width: config.bricks_width,
-                height: config.bricks_height,
-            })
-            .insert(GameBrick {
-                health: config.bricks_health,
-            });
+        let path = match &brick.kind {
+            BrickKind::Moving { path, speed } => Some(BrickPath {
+                points: std::iter::once(brick.position)
+                    .chain(path.iter().copied())
+                    .collect(),
+                speed: *speed,
+                next: 1,
+            }),
+            _ => None,
+        };
+        spawn_brick(
+            &mut commands,
+            BrickSnapshot {
+                position: brick.position,
+                size: brick.size,
+                brick: GameBrick {
+                    health: brick.health,
+                    max_health: brick.health,
+                    color: brick.color,
+                    kind: brick.kind.clone(),
+                },
+                path,
+            },
+        );
+
+        if brick.kind != BrickKind::Indestructible {
+            total_bricks += 1;
+        }
     }
+
+    commands.insert_resource(BricksCount {
+        total: total_bricks,
+        current: total_bricks,
+    });
 }
 
-fn spawn_grid(
-    mut pos: Vec2,
-    rows: u32,
-    cols: u32,
-    width: f32,
-    height: f32,
-    gap_x: f32,
-    gap_y: f32,
-) -> impl IntoIterator<Item = Vec3> {
-    if rows % 2 == 0 {
-        pos.y += (gap_y / 2.0 + height / 2.0) - (gap_y + height) * ((rows - 1) / 2) as f32;
-    } else {
-        pos.y += (gap_y + height) * ((rows - 1) / 2) as f32;
+fn bricks_movement(time: Res<Time>, mut bricks: Query<(&mut BrickPath, &mut Transform)>) {
+    for (mut path, mut transform) in bricks.iter_mut() {
+        let target = path.points[path.next];
+        let position = transform.translation.truncate();
+        let step = path.speed * time.delta_seconds();
+        let new_position = if position.distance(target) <= step {
+            path.next = (path.next + 1) % path.points.len();
+            target
+        } else {
+            position + (target - position).normalize() * step
+        };
+        transform.translation = new_position.extend(transform.translation.z);
     }
-    if cols % 2 == 0 {
-        pos.x -= (gap_x / 2.0 + width / 2.0) + (gap_x + width) * ((cols - 1) / 2) as f32;
-    } else {
-        pos.x -= (gap_x + width) * ((cols - 1) / 2) as f32;
-    }
-
-    (0..cols).flat_map(move |x| {
-        (0..rows).map(move |y| {
-            Vec3::new(
-                pos.x + x as f32 * (width + gap_x),
-                pos.y + y as f32 * (height + gap_y),
-                0.0,
-            )
-        })
-    })
 }
 
 fn bricks_collision(
-    config: Res<GameConfig>,
-    settings: Res<GameSettings>,
     mut commands: Commands,
     mut bricks_count: ResMut<BricksCount>,
-    mut score: ResMut<Score>,
     mut collision_events: EventReader<CollisionEvent>,
     mut game_events: EventWriter<GameEvents>,
-    mut bricks: Query<(Entity, &mut GameBrick)>,
-    mut audio: Query<(&mut Handle<AudioSource>, &mut PlaybackSettings)>,
+    mut gameplay_events: EventWriter<GameplayEvents>,
+    mut brick_hit_events: EventWriter<BrickHitEvent>,
+    mut brick_destroyed_events: EventWriter<BrickDestroyedEvent>,
+    balls: Query<&GameBall>,
+    mut bricks: Query<(Entity, &Transform, &Rectangle, &mut GameBrick)>,
 ) {
-    let Ok((mut audio_source, mut audio_settings)) = audio.get_single_mut() else {
-        return;
-    };
-    for event in collision_events.read() {
-        if let Ok((brick, mut game_brick)) = bricks.get_mut(event.entity2) {
-            *audio_source = config.bricks_sound.clone();
-            *audio_settings = PlaybackSettings {
-                mode: PlaybackMode::Once,
-                volume: Volume::new(settings.sound_volume),
-                speed: 1.0,
-                ..Default::default()
-            };
-            game_brick.health -= 1;
-            score.score += 1;
-            if game_brick.health == 0 {
-                bricks_count.current -= 1;
-                commands.entity(brick).despawn();
-            }
+    // Explosions damage other bricks, which can explode as well. Damage from
+    // an explosion counts as a hit of the ball that started it, laser bolts
+    // hit at the base speed.
+    let mut damaged: VecDeque<(Entity, f32)> = collision_events
+        .read()
+        .map(|event| {
+            let speed_mul = balls.get(event.entity1).map_or(1.0, |ball| ball.speed_mul);
+            (event.entity2, speed_mul)
+        })
+        .collect();
+    while let Some((entity, speed_mul)) = damaged.pop_front() {
+        let Ok((brick, transform, rect, mut game_brick)) = bricks.get_mut(entity) else {
+            continue;
+        };
+        // Several balls can hit the brick during the same step before it is despawned
+        if game_brick.health == 0 {
+            continue;
+        }
+        gameplay_events.send(GameplayEvents::BrickHit);
+        if game_brick.kind == BrickKind::Indestructible {
+            continue;
+        }
+        game_brick.health -= 1;
+        let position = transform.translation.truncate();
+        brick_hit_events.send(BrickHitEvent {
+            brick,
+            position,
+            points: game_brick.kind.score(),
+            speed_mul,
+        });
+        if game_brick.health != 0 {
+            continue;
+        }
+
+        gameplay_events.send(GameplayEvents::BrickDestroyed);
+        brick_destroyed_events.send(BrickDestroyedEvent {
+            position,
+            size: Vec2::new(rect.width, rect.height),
+            color: game_brick.color,
+            speed_mul,
+        });
+        bricks_count.current -= 1;
+        commands.entity(brick).despawn();
+        // Only the last brick destroyed completes the level
+        if bricks_count.current == 0 {
+            gameplay_events.send(GameplayEvents::LevelCleared);
+            game_events.send(GameEvents::LevelComplete);
+        }
+
+        if let BrickKind::Explosive { radius } = game_brick.kind {
+            damaged.extend(
+                bricks
+                    .iter()
+                    .filter(|(_, transform, _, _)| {
+                        transform.translation.truncate().distance(position) <= radius
+                    })
+                    .map(|(entity, _, _, _)| (entity, speed_mul)),
+            );
         }
-    }
-    if bricks_count.current == 0 {
-        game_events.send(GameEvents::EndGame);
     }
 }
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Size of the grid cell in the scene units. Bricks are 10-20 units wide,
+/// so each of them occupies only a few cells.
+pub const BROAD_PHASE_CELL_SIZE: f32 = 20.0;
+
+/// Uniform grid over static rectangles. Allows to find rectangles
+/// near some area without checking every rectangle in the scene.
+#[derive(Resource, Debug)]
+pub struct BroadPhase {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    /// Range of cells occupied by each entity, used to remove it from the grid
+    entities: HashMap<Entity, (IVec2, IVec2)>,
+}
+
+impl Default for BroadPhase {
+    fn default() -> Self {
+        Self::new(BROAD_PHASE_CELL_SIZE)
+    }
+}
+
+impl BroadPhase {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entities: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Adds the entity occupying the area from `min` to `max` to the grid.
+    /// If the entity is already in the grid, it is moved.
+    pub fn insert(&mut self, entity: Entity, min: Vec2, max: Vec2) {
+        self.remove(entity);
+
+        let (min, max) = (self.cell(min), self.cell(max));
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entity);
+            }
+        }
+        self.entities.insert(entity, (min, max));
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some((min, max)) = self.entities.remove(&entity) else {
+            return;
+        };
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                let cell = IVec2::new(x, y);
+                if let Some(entities) = self.cells.get_mut(&cell) {
+                    entities.retain(|e| *e != entity);
+                    if entities.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collects entities from the cells overlapping the area from `min` to `max`
+    /// into the `result`. Each entity is reported once.
+    pub fn query(&self, min: Vec2, max: Vec2, result: &mut Vec<Entity>) {
+        result.clear();
+
+        let (min, max) = (self.cell(min), self.cell(max));
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                if let Some(entities) = self.cells.get(&IVec2::new(x, y)) {
+                    result.extend(entities.iter().copied());
+                }
+            }
+        }
+        result.sort_unstable();
+        result.dedup();
+    }
+}
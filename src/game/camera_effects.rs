@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use crate::config::{GameConfig, GameSettings};
+use crate::events::GameplayEvents;
+use crate::game::bricks::BrickDestroyedEvent;
+use crate::game::platform::GamePlatform;
+
+/// Trauma of a brick destroyed by a ball at the base speed
+const BRICK_TRAUMA: f32 = 0.12;
+const LIFE_LOST_TRAUMA: f32 = 0.6;
+/// Trauma lost per second
+const TRAUMA_DECAY: f32 = 1.2;
+/// Camera offset in the scene units and roll in radians at full trauma
+const MAX_SHAKE_OFFSET: f32 = 4.0;
+const MAX_SHAKE_ROLL: f32 = 0.03;
+/// Shake oscillations per second
+const SHAKE_FREQUENCY: f32 = 25.0;
+/// Part of the platform offset from the scene center the camera follows
+const FOLLOW_AMOUNT: f32 = 0.08;
+/// Part of the remaining distance the camera follows per second
+const FOLLOW_SPEED: f32 = 4.0;
+/// Part of the camera distance the zoom punch brings it closer by
+const PUNCH_ZOOM: f32 = 0.08;
+/// Time in seconds the zoom punch takes
+const PUNCH_DURATION: f32 = 0.5;
+
+/// Shake, platform follow and zoom punch of the camera. Effects are only
+/// visual and are scaled by the camera motion setting.
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (camera_effects_setup, camera_effects_events).chain(),
+        );
+        // Applied over the transform set by the config
+        app.add_systems(
+            PostUpdate,
+            camera_effects_update.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Motion of the camera on top of its place from the config
+#[derive(Component, Debug, Default)]
+pub struct CameraEffects {
+    /// Shake strength from 0 to 1, the shake grows with its square
+    pub trauma: f32,
+    /// Horizontal camera offset following the platform
+    follow: f32,
+    /// Time left of the zoom punch
+    punch: f32,
+    time: f32,
+}
+
+impl CameraEffects {
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    pub fn punch(&mut self) {
+        self.punch = PUNCH_DURATION;
+    }
+}
+
+fn camera_effects_setup(mut commands: Commands, cameras: Query<Entity, Added<Camera3d>>) {
+    for entity in cameras.iter() {
+        commands.entity(entity).insert(CameraEffects::default());
+    }
+}
+
+fn camera_effects_events(
+    mut brick_destroyed_events: EventReader<BrickDestroyedEvent>,
+    mut gameplay_events: EventReader<GameplayEvents>,
+    mut cameras: Query<&mut CameraEffects>,
+) {
+    let mut trauma = 0.0;
+    let mut punch = false;
+    // Faster balls hit harder
+    for event in brick_destroyed_events.read() {
+        trauma += BRICK_TRAUMA * event.speed_mul;
+    }
+    for event in gameplay_events.read() {
+        match event {
+            GameplayEvents::LifeLost => trauma += LIFE_LOST_TRAUMA,
+            GameplayEvents::LevelCleared => punch = true,
+            _ => {}
+        }
+    }
+
+    for mut effects in cameras.iter_mut() {
+        effects.add_trauma(trauma);
+        if punch {
+            effects.punch();
+        }
+    }
+}
+
+fn camera_effects_update(
+    config: Res<GameConfig>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    platform: Query<&Transform, (With<GamePlatform>, Without<CameraEffects>)>,
+    mut cameras: Query<(&mut CameraEffects, &mut Transform)>,
+) {
+    let dt = time.delta_seconds();
+    let motion = settings.camera_motion.multiplier();
+    let center = config.scene_center();
+    let follow_target = platform.get_single().map_or(0.0, |platform| {
+        (platform.translation.x - center.x) * FOLLOW_AMOUNT
+    });
+
+    for (mut effects, mut transform) in cameras.iter_mut() {
+        effects.time += dt;
+        effects.trauma = (effects.trauma - TRAUMA_DECAY * dt).max(0.0);
+        effects.punch = (effects.punch - dt).max(0.0);
+        effects.follow += (follow_target - effects.follow) * (FOLLOW_SPEED * dt).min(1.0);
+
+        // Sines of unrelated frequencies look random and stay smooth
+        let t = effects.time * SHAKE_FREQUENCY;
+        let noise = |seed: f32| ((t + seed).sin() + (t * 1.7 + seed * 3.1).sin() * 0.5) / 1.5;
+        let shake = effects.trauma * effects.trauma * motion;
+        let offset = Vec3::new(noise(0.0), noise(10.0), 0.0) * MAX_SHAKE_OFFSET * shake;
+        let roll = noise(20.0) * MAX_SHAKE_ROLL * shake;
+
+        // Camera moves aside with the platform and keeps looking at the
+        // center, which tilts the scene
+        let mut position = config.camera_transform().translation;
+        position.x += effects.follow * motion;
+        // Punch zooms in at once and eases back out
+        let punch = (effects.punch / PUNCH_DURATION).powi(2) * motion;
+        position = center + (position - center) * (1.0 - PUNCH_ZOOM * punch);
+
+        *transform =
+            Transform::from_translation(position + offset).looking_at(center + offset, Vec3::Y);
+        transform.rotate_local_z(roll);
+    }
+}
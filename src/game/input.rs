@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Action, GameConfig, GameSettings, InputScheme};
+use crate::game::physics::PhysicsSet;
+use crate::game::platform::GamePlatform;
+use crate::game::replay::ReplayPlayback;
+use crate::game::GameState;
+
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerInput>();
+        app.init_resource::<WorldCursor>();
+        app.init_resource::<LaunchPressed>();
+        app.configure_sets(FixedUpdate, PlayerInputSet.before(PhysicsSet::Movement));
+        // Replays provide the input themselves
+        app.add_systems(
+            Update,
+            launch_input
+                .run_if(in_state(GameState::InGame))
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            player_input
+                .in_set(PlayerInputSet)
+                .run_if(in_state(GameState::InGame))
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
+    }
+}
+
+/// Systems filling the [`PlayerInput`] for the step
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
+/// Cursor position on the scene plane. Updated by the UI from the window,
+/// tests can set it directly.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct WorldCursor(pub Vec3);
+
+/// Actions of the player during a single fixed step. Gameplay reads input
+/// only from here, so a step can be repeated by feeding the same input.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    /// Requested horizontal velocity of the platform
+    pub movement: f32,
+    /// Launch balls attached to the platform
+    pub launch: bool,
+    /// Point to launch balls towards, straight up if missing
+    pub aim: Option<Vec2>,
+}
+
+/// Launch pressed since the last step. Frames and steps do not match,
+/// so a press is kept until a step picks it up.
+#[derive(Resource, Debug, Default)]
+struct LaunchPressed(bool);
+
+fn launch_input(
+    settings: Res<GameSettings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut launch_pressed: ResMut<LaunchPressed>,
+) {
+    launch_pressed.0 |= match settings.input_scheme {
+        InputScheme::Keyboard => settings.key_bindings.just_pressed(&keys, Action::Launch),
+        InputScheme::Mouse => mouse_buttons.just_pressed(MouseButton::Left),
+        InputScheme::Gamepad => gamepads.iter().any(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South))
+        }),
+    };
+}
+
+fn player_input(
+    config: Res<GameConfig>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<WorldCursor>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    platform: Query<(&GamePlatform, &Transform)>,
+    mut launch_pressed: ResMut<LaunchPressed>,
+    mut input: ResMut<PlayerInput>,
+) {
+    let Ok((platform, transform)) = platform.get_single() else {
+        *input = PlayerInput::default();
+        return;
+    };
+
+    input.movement = match settings.input_scheme {
+        InputScheme::Keyboard => {
+            let mut direction = 0.0;
+            if settings.key_bindings.pressed(&keys, Action::MoveLeft) {
+                direction -= 1.0;
+            }
+            if settings.key_bindings.pressed(&keys, Action::MoveRight) {
+                direction += 1.0;
+            }
+            direction * platform.speed
+        }
+        InputScheme::Mouse => {
+            // Reach the cursor within a step, but not faster than the speed cap
+            let max_speed = config.platform_mouse_max_speed;
+            ((cursor.0.x - transform.translation.x) / time.delta_seconds())
+                .clamp(-max_speed, max_speed)
+        }
+        InputScheme::Gamepad => {
+            let mut direction = 0.0;
+            if let Some(gamepad) = gamepads.iter().next() {
+                direction = gamepad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or_default();
+                if gamepad_buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadLeft))
+                {
+                    direction = -1.0;
+                }
+                if gamepad_buttons
+                    .pressed(GamepadButton::new(gamepad, GamepadButtonType::DPadRight))
+                {
+                    direction = 1.0;
+                }
+            }
+            direction * platform.speed
+        }
+    };
+
+    input.launch = std::mem::take(&mut launch_pressed.0);
+    // Gamepad has no cursor to aim with
+    input.aim = match settings.input_scheme {
+        InputScheme::Gamepad => None,
+        _ if input.launch => Some(cursor.0.truncate()),
+        _ => None,
+    };
+}
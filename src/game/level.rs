@@ -0,0 +1,314 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState, UntypedAssetId};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::deserialize_hex_color;
+use crate::game::GameState;
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Level>();
+        app.init_asset::<Campaign>();
+        app.init_asset_loader::<LevelLoader>();
+        app.init_asset_loader::<CampaignLoader>();
+        app.insert_resource(CurrentLevel { index: 0 });
+        app.add_systems(PreStartup, campaign_load);
+        app.add_systems(OnExit(GameState::LevelComplete), level_advance);
+    }
+}
+
+/// Brick layout of a single level.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub bricks: Vec<LevelBrick>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelBrick {
+    /// Center of the brick in the scene coordinates
+    pub position: Vec2,
+    pub size: Vec2,
+    pub health: u32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub color: Color,
+    #[serde(default)]
+    pub kind: BrickKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BrickKind {
+    #[default]
+    Normal,
+    /// Color darkens as the health drops
+    MultiHit,
+    /// Can not be destroyed and is not required to complete the level
+    Indestructible,
+    /// Damages all bricks within the `radius` when destroyed
+    Explosive { radius: f32 },
+    /// Moves through the `path` points in the scene coordinates with the `speed`
+    /// and then back to the starting position
+    Moving { path: Vec<Vec2>, speed: f32 },
+}
+
+impl BrickKind {
+    /// Score for each hit of the brick
+    pub fn score(&self) -> u32 {
+        match self {
+            BrickKind::Normal => 1,
+            BrickKind::MultiHit => 2,
+            BrickKind::Indestructible => 0,
+            BrickKind::Explosive { .. } => 3,
+            BrickKind::Moving { .. } => 5,
+        }
+    }
+}
+
+/// Ordered list of levels played one after another.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct Campaign {
+    pub levels: Vec<Handle<Level>>,
+}
+
+/// Campaign file format. Levels are paths relative to the assets folder.
+#[derive(Debug, Clone, Deserialize)]
+struct CampaignDescription {
+    levels: Vec<String>,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+/// Index of the level in the campaign that is currently played.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentLevel {
+    pub index: usize,
+}
+
+/// Whether a level of the campaign can be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelStatus {
+    Loading,
+    Ready,
+    /// Level or the campaign failed to load, or there is no such level
+    Failed,
+}
+
+/// Level that could not be played. Shown in the main menu the game goes back to.
+#[derive(Resource, Debug, Clone)]
+pub struct LevelError(pub String);
+
+/// Campaign with the load state of its levels
+#[derive(SystemParam)]
+pub struct CampaignLevels<'w> {
+    asset_server: Res<'w, AssetServer>,
+    campaign: Res<'w, CampaignHandle>,
+    campaigns: Res<'w, Assets<Campaign>>,
+    levels: Res<'w, Assets<Level>>,
+}
+
+impl CampaignLevels<'_> {
+    pub fn level(&self, index: usize) -> Option<&Level> {
+        let campaign = self.campaigns.get(&self.campaign.0)?;
+        self.levels.get(campaign.levels.get(index)?)
+    }
+
+    pub fn status(&self, index: usize) -> LevelStatus {
+        let Some(campaign) = self.campaigns.get(&self.campaign.0) else {
+            return self.pending_status(self.campaign.0.id());
+        };
+        match campaign.levels.get(index) {
+            Some(handle) if self.levels.contains(handle) => LevelStatus::Ready,
+            Some(handle) => self.pending_status(handle.id()),
+            None => LevelStatus::Failed,
+        }
+    }
+
+    /// Asset which is not there yet is either being loaded or never will be
+    fn pending_status(&self, id: impl Into<UntypedAssetId>) -> LevelStatus {
+        match self.asset_server.load_state(id) {
+            LoadState::Loading => LevelStatus::Loading,
+            _ => LevelStatus::Failed,
+        }
+    }
+}
+
+impl CurrentLevel {
+    pub fn is_last(&self, campaign: &Campaign) -> bool {
+        campaign.levels.len() <= self.index + 1
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LevelLoaderError {
+    #[error("could not read level file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("level \"{0}\" has no destructible bricks")]
+    NoBricks(String),
+    #[error("brick #{index} at {position} has invalid size {size}, both sides must be positive")]
+    InvalidSize {
+        index: usize,
+        position: Vec2,
+        size: Vec2,
+    },
+    #[error("brick #{index} at {position} has 0 health")]
+    ZeroHealth { index: usize, position: Vec2 },
+    #[error("brick #{index} at {position} has invalid kind: {reason}")]
+    InvalidKind {
+        index: usize,
+        position: Vec2,
+        reason: &'static str,
+    },
+    #[error("brick #{first} at {first_position} overlaps brick #{second} at {second_position}")]
+    Overlap {
+        first: usize,
+        first_position: Vec2,
+        second: usize,
+        second_position: Vec2,
+    },
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level = ron::de::from_bytes::<Level>(&bytes)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+impl Level {
+    fn validate(&self) -> Result<(), LevelLoaderError> {
+        // Level is completed once all destructible bricks are destroyed
+        if self
+            .bricks
+            .iter()
+            .all(|brick| brick.kind == BrickKind::Indestructible)
+        {
+            return Err(LevelLoaderError::NoBricks(self.name.clone()));
+        }
+
+        for (index, brick) in self.bricks.iter().enumerate() {
+            if brick.size.x <= 0.0 || brick.size.y <= 0.0 {
+                return Err(LevelLoaderError::InvalidSize {
+                    index,
+                    position: brick.position,
+                    size: brick.size,
+                });
+            }
+            if brick.health == 0 {
+                return Err(LevelLoaderError::ZeroHealth {
+                    index,
+                    position: brick.position,
+                });
+            }
+            let invalid_kind = match &brick.kind {
+                BrickKind::Explosive { radius } if *radius <= 0.0 => {
+                    Some("explosion radius must be positive")
+                }
+                BrickKind::Moving { path, .. } if path.is_empty() => Some("path is empty"),
+                BrickKind::Moving { speed, .. } if *speed <= 0.0 => Some("speed must be positive"),
+                _ => None,
+            };
+            if let Some(reason) = invalid_kind {
+                return Err(LevelLoaderError::InvalidKind {
+                    index,
+                    position: brick.position,
+                    reason,
+                });
+            }
+        }
+
+        for (first, a) in self.bricks.iter().enumerate() {
+            for (second, b) in self.bricks.iter().enumerate().skip(first + 1) {
+                let distance = (a.position - b.position).abs() * 2.0;
+                if distance.x < a.size.x + b.size.x && distance.y < a.size.y + b.size.y {
+                    return Err(LevelLoaderError::Overlap {
+                        first,
+                        first_position: a.position,
+                        second,
+                        second_position: b.position,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CampaignLoaderError {
+    #[error("could not read campaign file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse campaign file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("campaign has no levels")]
+    NoLevels,
+}
+
+#[derive(Default)]
+pub struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let description = ron::de::from_bytes::<CampaignDescription>(&bytes)?;
+        if description.levels.is_empty() {
+            return Err(CampaignLoaderError::NoLevels);
+        }
+        let levels = description
+            .levels
+            .into_iter()
+            .map(|path| load_context.load(path))
+            .collect();
+        Ok(Campaign { levels })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+fn campaign_load(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CampaignHandle(
+        asset_server.load("levels/main.campaign.ron"),
+    ));
+}
+
+fn level_advance(mut current_level: ResMut<CurrentLevel>) {
+    current_level.index += 1;
+}
@@ -1,16 +1,40 @@
 use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::events::GameEvents;
+use crate::storage::DataDir;
 
 pub mod ball;
+pub mod ball_trail;
 pub mod bricks;
+pub mod broad_phase;
+pub mod camera_effects;
+pub mod input;
+pub mod level;
+pub mod particles;
 pub mod physics;
 pub mod platform;
+pub mod power_ups;
+pub mod replay;
 pub mod scene;
+pub mod scoring;
+pub mod snapshot;
+pub mod visuals;
 
 use ball::BallPlugin;
-use bricks::BricksPlugin;
+use bricks::{BricksPlugin, Score};
+use input::PlayerInputPlugin;
+use level::{CurrentLevel, LevelPlugin};
 use physics::PhysicsPlugin;
-use platform::PlatformPlugin;
+use platform::{PlatformLifes, PlatformPlugin};
+use power_ups::PowerUpsPlugin;
+use replay::{ReplayPlayback, ReplayPlugin, ReplayRecorder};
 use scene::ScenePlugin;
+use scoring::ScoringPlugin;
+use snapshot::{RestoredRun, RunSnapshot, SnapshotPlugin};
 
 pub struct GamePlugin;
 
@@ -18,13 +42,43 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>();
         app.enable_state_scoped_entities::<GameState>();
+        app.add_computed_state::<InLevel>();
+        app.enable_state_scoped_entities::<InLevel>();
+        app.insert_resource(GameRng(ChaCha8Rng::from_entropy()));
+
+        // A new run starts either from the main menu or from the end game screen
+        app.add_systems(
+            OnTransition {
+                exited: GameState::NotInGame,
+                entered: GameState::InGame,
+            },
+            new_run,
+        );
+        app.add_systems(
+            OnTransition {
+                exited: GameState::EndGame,
+                entered: GameState::InGame,
+            },
+            new_run,
+        );
+        app.add_systems(FixedLast, level_end_step);
+
+        // Simulation time stands still while paused
+        app.add_systems(OnEnter(GameState::Paused), time_pause);
+        app.add_systems(OnExit(GameState::Paused), time_unpause);
 
         app.add_plugins((
             PhysicsPlugin { debug: false },
             BallPlugin,
             BricksPlugin,
+            LevelPlugin,
             PlatformPlugin,
+            PlayerInputPlugin,
+            PowerUpsPlugin,
+            ReplayPlugin,
             ScenePlugin,
+            ScoringPlugin,
+            SnapshotPlugin,
         ));
     }
 }
@@ -36,5 +90,82 @@ pub enum GameState {
     NotInGame,
     InGame,
     Paused,
+    LevelComplete,
     EndGame,
 }
+
+/// Active while a level is played, including while it is paused.
+/// Level entities are scoped to it, so pausing does not despawn them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InLevel;
+
+impl ComputedStates for InLevel {
+    type SourceStates = GameState;
+
+    fn compute(sources: GameState) -> Option<Self> {
+        matches!(sources, GameState::InGame | GameState::Paused).then_some(InLevel)
+    }
+}
+
+fn time_pause(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn time_unpause(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Skips the rest of the fixed steps of the frame once the level is over. The number
+/// of steps left depends on the frame rate, so running them would break the replays.
+fn level_end_step(mut game_events: EventReader<GameEvents>, mut time: ResMut<Time<Fixed>>) {
+    let level_over = game_events
+        .read()
+        .any(|event| matches!(event, GameEvents::EndGame | GameEvents::LevelComplete));
+    if level_over {
+        let overstep = time.overstep();
+        time.discard_overstep(overstep);
+    }
+}
+
+/// Source of randomness for the gameplay
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct GameRng(pub ChaCha8Rng);
+
+/// Resets progress carried between levels of a run, or brings it back from the
+/// [`RestoredRun`]. Fresh runs are recorded. Replays start with the recorded seed
+/// and leave the saved run alone.
+fn new_run(
+    config: Res<GameConfig>,
+    data_dir: Res<DataDir>,
+    restored: Option<Res<RestoredRun>>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut commands: Commands,
+) {
+    if let Some(mut playback) = playback {
+        playback.restart();
+        commands.insert_resource(GameRng(ChaCha8Rng::seed_from_u64(playback.replay.seed)));
+    } else {
+        RunSnapshot::remove(&data_dir);
+        if let Some(restored) = restored {
+            let snapshot = &restored.0;
+            commands.insert_resource(snapshot.rng.clone());
+            commands.insert_resource(snapshot.score.clone());
+            commands.insert_resource(snapshot.lifes.clone());
+            commands.insert_resource(CurrentLevel {
+                index: snapshot.level,
+            });
+            return;
+        }
+
+        let seed = rand::thread_rng().gen();
+        commands.insert_resource(GameRng(ChaCha8Rng::seed_from_u64(seed)));
+        commands.insert_resource(ReplayRecorder::new(seed));
+    }
+
+    commands.insert_resource(Score { score: 0 });
+    commands.insert_resource(PlatformLifes {
+        max: config.platform_lifes,
+        current: config.platform_lifes,
+    });
+    commands.insert_resource(CurrentLevel { index: 0 });
+}
@@ -0,0 +1,363 @@
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::Rng;
+
+use crate::config::{GameConfig, GameSettings};
+use crate::events::GameplayEvents;
+use crate::game::bricks::BrickDestroyedEvent;
+use crate::game::physics::{Ball, CollisionEvent};
+use crate::game::platform::GamePlatform;
+use crate::game::InLevel;
+
+/// Particles are drawn in front of the level
+const PARTICLES_Z: f32 = 1.0;
+const SPARK_COLOR: Color = Color::srgb(1.0, 0.9, 0.5);
+/// Particle color changes over its life in steps, each of them has its own material
+const PARTICLE_COLOR_STEPS: usize = 8;
+
+/// Simple particles computed on the CPU. Particles are only visual, they use
+/// their own randomness, so the game plays the same with any density.
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParticleMesh>();
+        app.init_resource::<ParticleMaterials>();
+        app.add_systems(
+            Update,
+            (
+                (debris_emit, sparks_emit, life_lost_emit).run_if(in_state(InLevel)),
+                emitters_update,
+                particles_update,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Describes particles spawned by a [`ParticleEmitter`]
+#[derive(Debug, Clone)]
+pub struct ParticleEffect {
+    /// Particles spawned at once when the emitter starts
+    pub burst: u32,
+    /// Particles spawned per second while the emitter lives
+    pub rate: f32,
+    /// Time in seconds the emitter lives
+    pub duration: f32,
+    /// Particles fly in the `direction`, turned by up to the `spread`
+    /// radians to either side
+    pub direction: Vec2,
+    pub spread: f32,
+    pub speed: RangeInclusive<f32>,
+    /// Time in seconds each particle lives
+    pub lifetime: RangeInclusive<f32>,
+    pub size: f32,
+    /// Downward acceleration of the particles
+    pub gravity: f32,
+    /// Particle color goes from the first to the second over its life
+    pub colors: (Color, Color),
+}
+
+impl ParticleEffect {
+    /// Effect with the number of particles changed by the `multiplier`
+    pub fn scaled(mut self, multiplier: f32) -> Self {
+        self.burst = (self.burst as f32 * multiplier).round() as u32;
+        self.rate *= multiplier;
+        self
+    }
+}
+
+/// Spawns particles of the effect around its transform
+#[derive(Component, Debug)]
+pub struct ParticleEmitter {
+    effect: ParticleEffect,
+    age: f32,
+    /// Part of a particle carried to the next frame by the `rate`
+    pending: f32,
+    started: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: ParticleEffect) -> Self {
+        Self {
+            effect,
+            age: 0.0,
+            pending: 0.0,
+            started: false,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    gravity: f32,
+    palette: PaletteKey,
+    /// Color step of the material in use
+    step: usize,
+}
+
+/// Start and end colors of a particle
+type PaletteKey = [[u8; 4]; 2];
+
+/// Particles of the same colors share the materials of their color steps
+#[derive(Resource, Debug, Default)]
+struct ParticleMaterials {
+    palettes: HashMap<PaletteKey, Vec<Handle<StandardMaterial>>>,
+}
+
+impl ParticleMaterials {
+    fn key(colors: (Color, Color)) -> PaletteKey {
+        [
+            colors.0.to_srgba().to_u8_array(),
+            colors.1.to_srgba().to_u8_array(),
+        ]
+    }
+
+    fn material(
+        &mut self,
+        materials: &mut Assets<StandardMaterial>,
+        key: PaletteKey,
+        step: usize,
+    ) -> Handle<StandardMaterial> {
+        self.palettes.entry(key).or_insert_with(|| {
+            let [from, to] = key.map(|[r, g, b, a]| Color::srgba_u8(r, g, b, a));
+            (0..PARTICLE_COLOR_STEPS)
+                .map(|step| {
+                    let mix = step as f32 / (PARTICLE_COLOR_STEPS - 1) as f32;
+                    materials.add(StandardMaterial {
+                        base_color: Color::BLACK,
+                        emissive: from.mix(&to, mix).into(),
+                        ..default()
+                    })
+                })
+                .collect()
+        })[step]
+            .clone()
+    }
+}
+
+/// All particles share the same mesh
+#[derive(Resource, Debug)]
+struct ParticleMesh(Handle<Mesh>);
+
+impl FromWorld for ParticleMesh {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        ParticleMesh(meshes.add(Mesh::from(Cuboid::new(1.0, 1.0, 1.0))))
+    }
+}
+
+/// Spawns the emitter with the effect scaled to the particle density setting
+fn spawn_emitter(
+    commands: &mut Commands,
+    settings: &GameSettings,
+    position: Vec2,
+    effect: ParticleEffect,
+) {
+    let effect = effect.scaled(settings.particle_density.multiplier());
+    if effect.burst == 0 && effect.rate <= 0.0 {
+        return;
+    }
+    commands
+        .spawn(TransformBundle::from_transform(
+            Transform::from_translation(position.extend(PARTICLES_Z)),
+        ))
+        .insert(StateScoped(InLevel))
+        .insert(ParticleEmitter::new(effect));
+}
+
+/// Pieces of the destroyed brick falling apart
+fn debris(color: Color) -> ParticleEffect {
+    ParticleEffect {
+        burst: 16,
+        rate: 0.0,
+        duration: 0.0,
+        direction: Vec2::Y,
+        spread: std::f32::consts::PI,
+        speed: 10.0..=40.0,
+        lifetime: 0.4..=0.8,
+        size: 1.5,
+        gravity: 80.0,
+        colors: (color, Color::BLACK),
+    }
+}
+
+/// Short flash where something hit a surface with the `normal`
+fn sparks(normal: Vec2) -> ParticleEffect {
+    ParticleEffect {
+        burst: 6,
+        rate: 0.0,
+        duration: 0.0,
+        direction: normal,
+        spread: std::f32::consts::FRAC_PI_3,
+        speed: 30.0..=60.0,
+        lifetime: 0.1..=0.25,
+        size: 0.8,
+        gravity: 0.0,
+        colors: (SPARK_COLOR, Color::BLACK),
+    }
+}
+
+/// Fountain out of the platform when the last ball is lost
+fn life_lost(color: Color) -> ParticleEffect {
+    ParticleEffect {
+        burst: 30,
+        rate: 60.0,
+        duration: 0.3,
+        direction: Vec2::Y,
+        spread: std::f32::consts::FRAC_PI_4,
+        speed: 40.0..=90.0,
+        lifetime: 0.6..=1.2,
+        size: 2.0,
+        gravity: 120.0,
+        colors: (color, Color::BLACK),
+    }
+}
+
+fn debris_emit(
+    settings: Res<GameSettings>,
+    mut commands: Commands,
+    mut brick_destroyed_events: EventReader<BrickDestroyedEvent>,
+) {
+    for event in brick_destroyed_events.read() {
+        spawn_emitter(
+            &mut commands,
+            &settings,
+            event.position,
+            debris(event.color),
+        );
+    }
+}
+
+fn sparks_emit(
+    settings: Res<GameSettings>,
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    balls: Query<(), With<Ball>>,
+) {
+    // Only balls and bolts spark, the platform pushing against a wall does not
+    for event in collision_events.read() {
+        if balls.contains(event.entity1) {
+            spawn_emitter(
+                &mut commands,
+                &settings,
+                event.collision_point,
+                sparks(event.normal),
+            );
+        }
+    }
+}
+
+fn life_lost_emit(
+    config: Res<GameConfig>,
+    settings: Res<GameSettings>,
+    mut commands: Commands,
+    mut gameplay_events: EventReader<GameplayEvents>,
+    platform: Query<&Transform, With<GamePlatform>>,
+) {
+    for event in gameplay_events.read() {
+        if *event != GameplayEvents::LifeLost {
+            continue;
+        }
+        let Ok(transform) = platform.get_single() else {
+            continue;
+        };
+        spawn_emitter(
+            &mut commands,
+            &settings,
+            transform.translation.truncate(),
+            life_lost(config.platform_color),
+        );
+    }
+}
+
+fn emitters_update(
+    time: Res<Time>,
+    particle_mesh: Res<ParticleMesh>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut particle_materials: ResMut<ParticleMaterials>,
+    mut emitters: Query<(Entity, &mut ParticleEmitter, &Transform)>,
+) {
+    let mut rng = rand::thread_rng();
+    for (entity, mut emitter, transform) in emitters.iter_mut() {
+        let mut count = 0;
+        if !emitter.started {
+            emitter.started = true;
+            count += emitter.effect.burst;
+        }
+        emitter.age += time.delta_seconds();
+        emitter.pending += emitter.effect.rate * time.delta_seconds();
+        count += emitter.pending as u32;
+        emitter.pending = emitter.pending.fract();
+
+        let effect = &emitter.effect;
+        let palette = ParticleMaterials::key(effect.colors);
+        for _ in 0..count {
+            let angle = effect.direction.to_angle() + rng.gen_range(-effect.spread..=effect.spread);
+            let speed = rng.gen_range(effect.speed.clone());
+            commands
+                .spawn(PbrBundle {
+                    mesh: particle_mesh.0.clone(),
+                    material: particle_materials.material(&mut materials, palette, 0),
+                    transform: Transform::from_translation(transform.translation)
+                        .with_scale(Vec3::splat(effect.size)),
+                    ..default()
+                })
+                .insert(StateScoped(InLevel))
+                .insert(Particle {
+                    velocity: Vec2::from_angle(angle) * speed,
+                    age: 0.0,
+                    lifetime: rng.gen_range(effect.lifetime.clone()),
+                    size: effect.size,
+                    gravity: effect.gravity,
+                    palette,
+                    step: 0,
+                });
+        }
+
+        if effect.duration <= emitter.age {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Moves particles, fades their color and shrinks them until they die out
+fn particles_update(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut particle_materials: ResMut<ParticleMaterials>,
+    mut particles: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Handle<StandardMaterial>,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut material) in particles.iter_mut() {
+        particle.age += dt;
+        if particle.lifetime <= particle.age {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+
+        let life = particle.age / particle.lifetime;
+        transform.scale = Vec3::splat(particle.size * (1.0 - life));
+        // Material is swapped only when the particle reaches the next color step
+        let step = ((life * PARTICLE_COLOR_STEPS as f32) as usize).min(PARTICLE_COLOR_STEPS - 1);
+        if step != particle.step {
+            particle.step = step;
+            *material = particle_materials.material(&mut materials, particle.palette, step);
+        }
+    }
+}
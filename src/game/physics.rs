@@ -1,5 +1,9 @@
+use crate::config::GameConfig;
+use crate::game::broad_phase::BroadPhase;
 use crate::game::GameState;
+use bevy::ecs::schedule::ExecutorKind;
 use bevy::prelude::*;
+use bevy::transform::TransformSystem;
 
 #[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PhysicsSet {
@@ -15,16 +19,59 @@ pub struct PhysicsPlugin {
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<CollisionEvent>();
+        app.add_event::<SensorEvent>();
+        app.init_resource::<BroadPhase>();
 
+        // Simulation runs with a fixed time step, so results do not depend on the frame rate.
+        // Systems without explicit order keep the same order only on a single thread,
+        // which replays depend on.
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+        app.configure_sets(
+            FixedUpdate,
+            (
+                PhysicsSet::Movement,
+                PhysicsSet::CollisionDetection,
+                PhysicsSet::CollisionResolution,
+            )
+                .chain(),
+        );
         app.add_systems(
             Update,
-            (ball_rect_collision_system, rect_rect_collision_system)
+            physics_tick_rate_update.run_if(resource_changed::<GameConfig>),
+        );
+
+        app.add_systems(FixedFirst, interpolation_restore);
+        app.add_systems(FixedLast, interpolation_store);
+        app.add_systems(
+            PostUpdate,
+            interpolation_apply.before(TransformSystem::TransformPropagate),
+        );
+
+        // Removals are tracked every frame, so despawns are never missed
+        // between fixed steps
+        app.add_systems(
+            FixedUpdate,
+            broad_phase_insert
+                .after(PhysicsSet::Movement)
+                .before(PhysicsSet::CollisionDetection),
+        );
+        app.add_systems(Last, broad_phase_remove);
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                ball_rect_collision_system,
+                rect_rect_collision_system,
+                sensor_system,
+            )
                 .in_set(PhysicsSet::CollisionDetection)
                 .run_if(in_state(GameState::InGame)),
         );
         if self.debug {
             app.add_systems(
-                Update,
+                FixedUpdate,
                 debug_physics_event.in_set(PhysicsSet::CollisionResolution),
             );
         }
@@ -45,69 +92,343 @@ pub struct Rectangle {
 #[derive(Component, Debug)]
 pub struct Dynamic;
 
+/// Rectangle which sets the direction of balls hitting its top side by itself.
+/// The ball stops at the contact instead of going on with the mirrored motion.
+#[derive(Component, Debug)]
+pub struct Paddle;
+
+/// Area which reports overlaps with [`Dynamic`] rectangles with [`SensorEvent`]s.
+/// Sensors do not block anything and are not pushed out.
+#[derive(Component, Debug)]
+pub struct Sensor {
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Smooths movement of the entity between fixed time steps.
+/// Physics works with the `current` translation, while [`Transform`]
+/// of the entity is interpolated between `previous` and `current` every frame.
+#[derive(Component, Debug)]
+pub struct Interpolated {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+impl Interpolated {
+    pub fn new(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            current: translation,
+        }
+    }
+}
+
 #[derive(Event, Debug)]
 pub struct CollisionEvent {
     pub entity1: Entity,
     pub entity2: Entity,
     pub collision_point: Vec2,
+    /// Normal of the `entity2` surface pointing towards the `entity1`
+    pub normal: Vec2,
+}
+
+#[derive(Event, Debug)]
+pub struct SensorEvent {
+    pub sensor: Entity,
+    pub entity: Entity,
+}
+
+fn physics_tick_rate_update(config: Res<GameConfig>, mut time: ResMut<Time<Fixed>>) {
+    time.set_timestep_hz(config.physics_tick_rate);
+}
+
+/// Brings back simulated translation before the fixed step
+fn interpolation_restore(mut interpolated: Query<(&mut Interpolated, &mut Transform)>) {
+    for (mut interpolated, mut transform) in interpolated.iter_mut() {
+        transform.translation = interpolated.current;
+        interpolated.previous = interpolated.current;
+    }
 }
 
+fn interpolation_store(mut interpolated: Query<(&mut Interpolated, &Transform)>) {
+    for (mut interpolated, transform) in interpolated.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+pub(crate) fn interpolation_apply(
+    time: Res<Time<Fixed>>,
+    mut interpolated: Query<(&Interpolated, &mut Transform)>,
+) {
+    let alpha = time.overstep_fraction();
+    for (interpolated, mut transform) in interpolated.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
+
+/// Adds new static rectangles to the [`BroadPhase`] and moves changed ones
+fn broad_phase_insert(
+    mut broad_phase: ResMut<BroadPhase>,
+    rectangles: Query<
+        (Entity, &Rectangle, &Transform),
+        (
+            Without<Dynamic>,
+            Or<(Changed<Rectangle>, Changed<Transform>)>,
+        ),
+    >,
+) {
+    for (entity, rect, transform) in rectangles.iter() {
+        let half_size = Vec2::new(rect.width, rect.height) / 2.0;
+        let center = transform.translation.truncate();
+        broad_phase.insert(entity, center - half_size, center + half_size);
+    }
+}
+
+fn broad_phase_remove(
+    mut broad_phase: ResMut<BroadPhase>,
+    mut removed: RemovedComponents<Rectangle>,
+) {
+    for entity in removed.read() {
+        broad_phase.remove(entity);
+    }
+}
+
+/// Maximum number of bounces the ball can make during a single step
+const MAX_BALL_BOUNCES: usize = 4;
+
 fn ball_rect_collision_system(
+    broad_phase: Res<BroadPhase>,
+    mut candidates: Local<Vec<Entity>>,
     mut collision_events: EventWriter<CollisionEvent>,
-    balls: Query<(Entity, &Ball, &Transform), With<Dynamic>>,
-    rectangles: Query<(Entity, &Rectangle, &Transform)>,
+    mut balls: Query<(Entity, &Ball, &Interpolated, &mut Transform), With<Dynamic>>,
+    rectangles: Query<(Entity, &Rectangle, &Transform, Has<Paddle>), Without<Ball>>,
+    dynamic_rectangles: Query<Entity, (With<Rectangle>, With<Dynamic>, Without<Ball>)>,
 ) {
-    for (ball_entity, ball, ball_transform) in balls.iter() {
-        for (rect_entity, rect, rect_transform) in rectangles.iter() {
-            if let Some(collision_point) =
-                ball_rect_collision(ball, ball_transform, rect, rect_transform)
-            {
-                collision_events.send(CollisionEvent {
-                    entity1: ball_entity,
-                    entity2: rect_entity,
-                    collision_point,
-                });
+    for (ball_entity, ball, interpolated, mut ball_transform) in balls.iter_mut() {
+        // The ball moved from the position it had at the start of the step
+        let mut position = interpolated.previous.truncate();
+        let mut motion = ball_transform.translation.truncate() - position;
+
+        for bounce in 0..MAX_BALL_BOUNCES {
+            // Only rectangles near the path of the ball can be hit
+            let radius = Vec2::splat(ball.radius);
+            broad_phase.query(
+                position.min(position + motion) - radius,
+                position.max(position + motion) + radius,
+                &mut candidates,
+            );
+            candidates.extend(dynamic_rectangles.iter());
+
+            let hit = rectangles
+                .iter_many(candidates.iter())
+                .filter_map(|(rect_entity, rect, rect_transform, paddle)| {
+                    ball_rect_sweep(
+                        position,
+                        ball.radius,
+                        motion,
+                        rect_transform.translation.truncate(),
+                        Vec2::new(rect.width, rect.height) / 2.0,
+                    )
+                    .map(|hit| (rect_entity, paddle, hit))
+                })
+                .min_by(|(_, _, a), (_, _, b)| a.time.total_cmp(&b.time));
+
+            let Some((rect_entity, paddle, hit)) = hit else {
+                position += motion;
+                break;
+            };
+
+            collision_events.send(CollisionEvent {
+                entity1: ball_entity,
+                entity2: rect_entity,
+                collision_point: hit.point,
+                normal: hit.normal,
+            });
+
+            // Move to the contact and continue with the reflected remaining motion
+            position += motion * hit.time;
+            // New direction is not known until the collision resolution
+            if paddle && 0.0 < hit.normal.y {
+                break;
+            }
+            let remaining = motion * (1.0 - hit.time);
+            motion = remaining - 2.0 * remaining.dot(hit.normal) * hit.normal;
+
+            // Motion left after the last bounce is dropped and the ball stays at the
+            // contact, which is outside of all rectangles. Moving it unchecked could put
+            // the ball inside one. Every contact is reported only while the motion goes
+            // into the surface, and the velocity has the direction of the motion, so the
+            // collision resolution reflects it by the same contacts in the same order.
+            // The ball leaves in the direction of the dropped motion and only loses
+            // a part of this step.
+            if bounce + 1 == MAX_BALL_BOUNCES {
+                debug!(
+                    "Ball {:?} bounced {} times in a step, {} of its motion is dropped",
+                    ball_entity,
+                    MAX_BALL_BOUNCES,
+                    motion.length()
+                );
             }
         }
+
+        ball_transform.translation = position.extend(ball_transform.translation.z);
     }
 }
 
-fn ball_rect_collision(
-    ball: &Ball,
-    ball_transform: &Transform,
-    rect: &Rectangle,
-    rect_transform: &Transform,
-) -> Option<Vec2> {
-    let mut px = ball_transform.translation.x;
-    let mut py = ball_transform.translation.y;
-    px = px.max(rect_transform.translation.x - rect.width / 2.0);
-    px = px.min(rect_transform.translation.x + rect.width / 2.0);
-    py = py.max(rect_transform.translation.y - rect.height / 2.0);
-    py = py.min(rect_transform.translation.y + rect.height / 2.0);
-
-    if (ball_transform.translation.x - px).powi(2) + (ball_transform.translation.y - py).powi(2)
-        < ball.radius.powi(2)
-    {
-        Some(Vec2::new(px, py))
-    } else {
-        None
+/// Contact found by the [`ball_rect_sweep`]
+#[derive(Debug, Clone, Copy)]
+pub struct SweepHit {
+    /// Fraction of the motion after which the ball touches the rectangle
+    pub time: f32,
+    /// Normal of the rectangle surface at the contact
+    pub normal: Vec2,
+    /// Contact point on the rectangle surface
+    pub point: Vec2,
+}
+
+/// Sweeps a circle starting at `start` and moving by `motion` against a rectangle.
+/// Only contacts where the circle moves into the rectangle are reported. If the circle
+/// already overlaps the rectangle, the contact is reported with the time of 0.
+pub fn ball_rect_sweep(
+    start: Vec2,
+    radius: f32,
+    motion: Vec2,
+    rect_center: Vec2,
+    rect_half_size: Vec2,
+) -> Option<SweepHit> {
+    let local = start - rect_center;
+
+    let closest = local.clamp(-rect_half_size, rect_half_size);
+    let offset = local - closest;
+    if offset.length_squared() < radius * radius {
+        let normal = if offset == Vec2::ZERO {
+            // The center is inside the rectangle, push out along the axis of the least penetration
+            let penetration = rect_half_size - local.abs();
+            if penetration.x < penetration.y {
+                Vec2::new(local.x.signum(), 0.0)
+            } else {
+                Vec2::new(0.0, local.y.signum())
+            }
+        } else {
+            offset.normalize()
+        };
+        if 0.0 <= motion.dot(normal) {
+            return None;
+        }
+        return Some(SweepHit {
+            time: 0.0,
+            normal,
+            point: rect_center + closest,
+        });
+    }
+
+    // The circle center against the rectangle expanded by the radius
+    let expanded = rect_half_size + Vec2::splat(radius);
+    let mut time_enter = 0.0_f32;
+    let mut time_exit = 1.0_f32;
+    let mut normal = Vec2::ZERO;
+    for axis in 0..2 {
+        if motion[axis].abs() < f32::EPSILON {
+            if expanded[axis] < local[axis].abs() {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (-expanded[axis] - local[axis]) / motion[axis];
+        let t2 = (expanded[axis] - local[axis]) / motion[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        // Touching the side at the start is a contact too, like after a bounce
+        // off the other side of a corner
+        if time_enter <= near {
+            time_enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = -motion[axis].signum();
+        }
+        time_exit = time_exit.min(far);
+        if time_exit < time_enter {
+            return None;
+        }
+    }
+
+    let contact = local + motion * time_enter;
+    if contact.x.abs() <= rect_half_size.x || contact.y.abs() <= rect_half_size.y {
+        // Hit one of the sides
+        if normal == Vec2::ZERO {
+            return None;
+        }
+        return Some(SweepHit {
+            time: time_enter,
+            normal,
+            point: rect_center + contact - normal * radius,
+        });
+    }
+
+    // Contact is in the corner region, sweep against the rounded corner
+    let corner = Vec2::new(
+        rect_half_size.x.copysign(contact.x),
+        rect_half_size.y.copysign(contact.y),
+    );
+    let to_start = local - corner;
+    let a = motion.length_squared();
+    let b = 2.0 * to_start.dot(motion);
+    let c = to_start.length_squared() - radius * radius;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
     }
+    let time = (-b - discriminant.sqrt()) / (2.0 * a);
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+    Some(SweepHit {
+        time,
+        normal: (to_start + motion * time) / radius,
+        point: rect_center + corner,
+    })
 }
 
 pub fn rect_rect_collision_system(
+    broad_phase: Res<BroadPhase>,
+    mut candidates: Local<Vec<Entity>>,
     mut collision_events: EventWriter<CollisionEvent>,
     dynamic_rectangles: Query<(Entity, &Rectangle, &Transform), With<Dynamic>>,
     rectangles: Query<(Entity, &Rectangle, &Transform), Without<Dynamic>>,
 ) {
     for (dyn_entity, dyn_rect, dyn_transform) in dynamic_rectangles.iter() {
-        for (rect_entity, rect, rect_transform) in rectangles.iter() {
-            if let Some(collision_point) =
+        let half_size = Vec2::new(dyn_rect.width, dyn_rect.height) / 2.0;
+        let center = dyn_transform.translation.truncate();
+        broad_phase.query(center - half_size, center + half_size, &mut candidates);
+
+        for (rect_entity, rect, rect_transform) in rectangles.iter_many(candidates.iter()) {
+            if let Some((collision_point, normal)) =
                 rect_rect_collision(dyn_rect, dyn_transform, rect, rect_transform)
             {
                 collision_events.send(CollisionEvent {
                     entity1: dyn_entity,
                     entity2: rect_entity,
                     collision_point,
+                    normal,
+                });
+            }
+        }
+    }
+}
+
+fn sensor_system(
+    mut sensor_events: EventWriter<SensorEvent>,
+    sensors: Query<(Entity, &Sensor, &Transform)>,
+    dynamic_rectangles: Query<(Entity, &Rectangle, &Transform), With<Dynamic>>,
+) {
+    for (sensor_entity, sensor, sensor_transform) in sensors.iter() {
+        let sensor_rect = Rectangle {
+            width: sensor.width,
+            height: sensor.height,
+        };
+        for (entity, rect, transform) in dynamic_rectangles.iter() {
+            if rect_rect_collision(rect, transform, &sensor_rect, sensor_transform).is_some() {
+                sensor_events.send(SensorEvent {
+                    sensor: sensor_entity,
+                    entity,
                 });
             }
         }
@@ -119,7 +440,7 @@ fn rect_rect_collision(
     dyn_transform: &Transform,
     rect: &Rectangle,
     rect_transform: &Transform,
-) -> Option<Vec2> {
+) -> Option<(Vec2, Vec2)> {
     let collision_x = dyn_transform.translation.x + dyn_rect.width / 2.0
         >= rect_transform.translation.x - rect.width / 2.0
         && rect_transform.translation.x + rect.width / 2.0
@@ -140,7 +461,15 @@ fn rect_rect_collision(
         let left = (dyn_transform.translation.x - dyn_rect.width / 2.0)
             .max(rect_transform.translation.x - rect.width / 2.0);
 
-        Some(Vec2::new((left + right) / 2.0, (top + bot) / 2.0))
+        // Push out along the axis of the smallest overlap
+        let offset = dyn_transform.translation - rect_transform.translation;
+        let normal = if right - left < top - bot {
+            Vec2::new(offset.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, offset.y.signum())
+        };
+
+        Some((Vec2::new((left + right) / 2.0, (top + bot) / 2.0), normal))
     } else {
         None
     }
@@ -162,3 +491,200 @@ fn debug_physics_event(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    #[test]
+    fn sweep_starting_inside_pushes_out() {
+        // Center of the ball is inside the rectangle, closer to its top side
+        let hit = ball_rect_sweep(
+            Vec2::new(0.0, 1.0),
+            1.0,
+            Vec2::new(0.0, -5.0),
+            Vec2::ZERO,
+            Vec2::new(10.0, 2.0),
+        )
+        .unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!(hit.normal, Vec2::Y);
+
+        // Moving out of the rectangle is not a contact
+        let hit = ball_rect_sweep(
+            Vec2::new(0.0, 1.0),
+            1.0,
+            Vec2::new(0.0, 5.0),
+            Vec2::ZERO,
+            Vec2::new(10.0, 2.0),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_hits_corner() {
+        let hit = ball_rect_sweep(
+            Vec2::new(-10.0, 10.0),
+            1.0,
+            Vec2::new(10.0, -10.0),
+            Vec2::ZERO,
+            Vec2::splat(5.0),
+        )
+        .unwrap();
+        // The ball touches the corner one radius away from it along the diagonal
+        let distance = Vec2::splat(5.0).length() - 1.0;
+        assert!((hit.time - distance / Vec2::splat(10.0).length()).abs() < EPSILON);
+        assert!((hit.normal - Vec2::new(-1.0, 1.0).normalize()).length() < EPSILON);
+        assert_eq!(hit.point, Vec2::new(-5.0, 5.0));
+
+        // Passing by the corner within the expanded rectangle misses it
+        let hit = ball_rect_sweep(
+            Vec2::new(-9.8, 1.8),
+            1.0,
+            Vec2::new(8.0, 8.0),
+            Vec2::ZERO,
+            Vec2::splat(5.0),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_grazes_at_end_of_motion() {
+        let hit = ball_rect_sweep(
+            Vec2::new(0.0, 10.0),
+            1.0,
+            Vec2::new(0.0, -4.0),
+            Vec2::ZERO,
+            Vec2::splat(5.0),
+        )
+        .unwrap();
+        assert_eq!(hit.time, 1.0);
+        assert_eq!(hit.normal, Vec2::Y);
+        assert_eq!(hit.point, Vec2::new(0.0, 5.0));
+
+        // Stopping short of the rectangle
+        let hit = ball_rect_sweep(
+            Vec2::new(0.0, 10.0),
+            1.0,
+            Vec2::new(0.0, -3.9),
+            Vec2::ZERO,
+            Vec2::splat(5.0),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn sweep_does_not_tunnel_through_thin_border() {
+        let config = GameConfig {
+            ball_radius: 1.0,
+            ball_speed: 600.0,
+            ..default()
+        };
+        let step =
+            config.ball_speed * config.ball_max_speed_multiplier / config.physics_tick_rate as f32;
+        let border_half_size = Vec2::new(100.0, 0.5);
+        let start = Vec2::new(0.0, border_half_size.y + config.ball_radius + 0.1);
+        let motion = Vec2::new(0.0, -step);
+        // Ball would end up entirely past the border
+        let end = start + motion;
+        assert!(end.y + config.ball_radius < -border_half_size.y);
+
+        let hit = ball_rect_sweep(
+            start,
+            config.ball_radius,
+            motion,
+            Vec2::ZERO,
+            border_half_size,
+        )
+        .unwrap();
+        assert!((hit.time - 0.1 / step).abs() < EPSILON);
+        assert_eq!(hit.normal, Vec2::Y);
+    }
+
+    #[test]
+    fn ball_bounces_out_of_corner_at_max_speed() {
+        let config = GameConfig {
+            ball_radius: 1.0,
+            ball_speed: 600.0,
+            ..default()
+        };
+        let step =
+            config.ball_speed * config.ball_max_speed_multiplier / config.physics_tick_rate as f32;
+        // Top right corner of the scene, the borders overlap like the scene ones
+        let walls = [
+            (Vec2::new(50.0, 100.0), Vec2::new(50.0, 0.5), Vec2::NEG_Y),
+            (Vec2::new(100.0, 50.0), Vec2::new(0.5, 50.0), Vec2::NEG_X),
+        ];
+        let corner = Vec2::new(100.0 - 0.5, 100.0 - 0.5) - Vec2::splat(config.ball_radius);
+
+        for degrees in (5..90).step_by(5) {
+            let mut world = World::new();
+            world.init_resource::<BroadPhase>();
+            world.init_resource::<Events<CollisionEvent>>();
+            for (center, half_size, _) in walls {
+                let entity = world
+                    .spawn(Rectangle {
+                        width: half_size.x * 2.0,
+                        height: half_size.y * 2.0,
+                    })
+                    .insert(Transform::from_translation(center.extend(0.0)))
+                    .id();
+                world.resource_mut::<BroadPhase>().insert(
+                    entity,
+                    center - half_size,
+                    center + half_size,
+                );
+            }
+
+            // Ball heads right into the corner and gets there within a step
+            let mut velocity = Vec2::from_angle((degrees as f32).to_radians());
+            let mut position = corner - velocity * step * 2.5;
+            let ball = world
+                .spawn((
+                    Ball {
+                        radius: config.ball_radius,
+                    },
+                    Interpolated::new(position.extend(0.0)),
+                    Transform::from_translation(position.extend(0.0)),
+                    Dynamic,
+                ))
+                .id();
+
+            for _ in 0..6 {
+                let mut entity = world.entity_mut(ball);
+                entity.get_mut::<Interpolated>().unwrap().previous = position.extend(0.0);
+                entity.get_mut::<Transform>().unwrap().translation =
+                    (position + velocity * step).extend(0.0);
+                world.run_system_once(ball_rect_collision_system);
+
+                // Velocity is reflected like the collision resolution of the ball does
+                for event in world.resource_mut::<Events<CollisionEvent>>().drain() {
+                    if velocity.dot(event.normal) < 0.0 {
+                        velocity -= 2.0 * velocity.dot(event.normal) * event.normal;
+                    }
+                }
+                position = world.get::<Transform>(ball).unwrap().translation.truncate();
+
+                for (center, half_size, _) in walls {
+                    let closest = center + (position - center).clamp(-half_size, half_size);
+                    let distance = position.distance(closest);
+                    assert!(
+                        config.ball_radius - EPSILON <= distance,
+                        "ball at {position} is inside a wall, approached at {degrees} degrees"
+                    );
+                }
+            }
+
+            for (_, _, normal) in walls {
+                assert!(
+                    0.0 < velocity.dot(normal),
+                    "ball moves with {velocity} into a wall, approached at {degrees} degrees"
+                );
+            }
+        }
+    }
+}
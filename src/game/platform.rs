@@ -1,9 +1,12 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::config::GameConfig;
-use crate::events::GameEvents;
-use crate::game::physics::{CollisionEvent, Dynamic, PhysicsSet, Rectangle};
-use crate::game::GameState;
+use crate::events::{GameEvents, GameplayEvents};
+use crate::game::input::PlayerInput;
+use crate::game::physics::{CollisionEvent, Dynamic, Interpolated, Paddle, PhysicsSet, Rectangle};
+use crate::game::snapshot::RestoredRun;
+use crate::game::{GameState, InLevel};
 
 use super::ball::{GameBall, GameBallEvent};
 
@@ -11,21 +14,21 @@ pub struct PlatformPlugin;
 
 impl Plugin for PlatformPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), platform_spawn);
+        app.add_systems(OnEnter(InLevel), platform_spawn);
         app.add_systems(
-            Update,
+            FixedUpdate,
             platform_movement
                 .in_set(PhysicsSet::Movement)
                 .run_if(in_state(GameState::InGame)),
         );
         app.add_systems(
-            Update,
+            FixedUpdate,
             platform_lifes
                 .in_set(PhysicsSet::CollisionDetection)
                 .run_if(in_state(GameState::InGame)),
         );
         app.add_systems(
-            Update,
+            FixedUpdate,
             platform_collision
                 .in_set(PhysicsSet::CollisionResolution)
                 .run_if(in_state(GameState::InGame)),
@@ -38,9 +41,13 @@ pub struct GamePlatform {
     pub width: f32,
     pub height: f32,
     pub speed: f32,
+    /// Current horizontal velocity, used to add english to the ball
+    pub velocity: f32,
+    /// Balls hitting the platform stay attached to it
+    pub sticky: bool,
 }
 
-#[derive(Debug, Clone, Resource)]
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
 pub struct PlatformLifes {
     pub max: u32,
     pub current: u32,
@@ -48,76 +55,81 @@ pub struct PlatformLifes {
 
 fn platform_spawn(
     config: Res<GameConfig>,
+    restored: Option<Res<RestoredRun>>,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    commands.insert_resource(PlatformLifes {
-        max: config.platform_lifes,
-        current: config.platform_lifes,
-    });
-
-    let material = materials.add(StandardMaterial {
-        emissive: config.platform_color.into(),
-        ..default()
-    });
-
+    let position = restored.map_or(
+        Vec2::new(config.scene_width as f32 / 2.0, 10.0),
+        |restored| restored.0.platform,
+    );
+    let transform = Transform::from_translation(position.extend(0.0));
     commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Mesh::from(Cuboid::new(
-                config.platform_width,
-                config.platform_height,
-                1.0,
-            ))),
-            material,
-            transform: Transform::from_xyz(config.scene_width as f32 / 2.0, 10.0, 0.0),
-            ..default()
-        })
-        .insert(StateScoped(GameState::InGame))
+        .spawn(TransformBundle::from_transform(transform))
+        .insert(StateScoped(InLevel))
         .insert(Rectangle {
             width: config.platform_width,
             height: config.platform_height,
         })
         .insert(Dynamic)
+        .insert(Paddle)
+        .insert(Interpolated::new(transform.translation))
         .insert(GamePlatform {
             width: config.platform_width,
             height: config.platform_height,
             speed: config.platform_speed,
+            velocity: 0.0,
+            sticky: false,
         });
 }
 
 fn platform_movement(
-    keys: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
-    mut platform: Query<(&GamePlatform, &mut Transform)>,
+    input: Res<PlayerInput>,
+    mut platform: Query<(&mut GamePlatform, &mut Transform)>,
 ) {
-    if let Ok((platform, mut transform)) = platform.get_single_mut() {
-        if keys.pressed(KeyCode::KeyA) {
-            transform.translation.x -= platform.speed * time.delta_seconds();
-        }
-
-        if keys.pressed(KeyCode::KeyD) {
-            transform.translation.x += platform.speed * time.delta_seconds();
-        }
-    }
+    let Ok((mut platform, mut transform)) = platform.get_single_mut() else {
+        return;
+    };
+    platform.velocity = input.movement;
+    transform.translation.x += platform.velocity * time.delta_seconds();
 }
 
+/// Despawns balls that fell below the platform. A life is lost
+/// only when the last ball leaves the play.
 fn platform_lifes(
+    mut commands: Commands,
     platform: Query<&Transform, With<GamePlatform>>,
-    ball: Query<&Transform, (With<GameBall>, Without<GamePlatform>)>,
+    balls: Query<(Entity, &Transform), (With<GameBall>, Without<GamePlatform>)>,
     mut lifes: ResMut<PlatformLifes>,
     mut game_events: EventWriter<GameEvents>,
+    mut gameplay_events: EventWriter<GameplayEvents>,
     mut ball_events: EventWriter<GameBallEvent>,
 ) {
-    if let (Ok(ball), Ok(platform)) = (ball.get_single(), platform.get_single()) {
+    let Ok(platform) = platform.get_single() else {
+        return;
+    };
+
+    let mut balls_left = 0;
+    let mut balls_lost = 0;
+    for (ball_entity, ball) in balls.iter() {
         if ball.translation.y < platform.translation.y {
-            lifes.current -= 1;
-            ball_events.send(GameBallEvent::ChangeState);
+            commands.entity(ball_entity).despawn_recursive();
+            balls_lost += 1;
+        } else {
+            balls_left += 1;
         }
-        if lifes.current == 0 {
-            game_events.send(GameEvents::EndGame);
+    }
+
+    if balls_lost != 0 && balls_left == 0 {
+        lifes.current = lifes.current.saturating_sub(1);
+        gameplay_events.send(GameplayEvents::LifeLost);
+        if lifes.current != 0 {
+            ball_events.send(GameBallEvent::SpawnAttached);
         }
     }
+    if lifes.current == 0 {
+        game_events.send(GameEvents::EndGame);
+    }
 }
 
 fn platform_collision(
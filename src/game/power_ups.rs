@@ -0,0 +1,357 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::events::GameplayEvents;
+use crate::game::ball::{GameBall, GameBallEvent};
+use crate::game::bricks::BrickDestroyedEvent;
+use crate::game::physics::{
+    Ball, CollisionEvent, Dynamic, Interpolated, PhysicsSet, Rectangle, Sensor, SensorEvent,
+};
+use crate::game::platform::{GamePlatform, PlatformLifes};
+use crate::game::snapshot::RestoredRun;
+use crate::game::{GameRng, GameState, InLevel};
+
+/// Platform width multiplier for each stack of the [`PowerUp::WidePlatform`]
+const WIDE_PLATFORM_MULTIPLIER: f32 = 1.3;
+/// Maximum number of the [`PowerUp::WidePlatform`] stacks that affect the platform
+const WIDE_PLATFORM_MAX_STACKS: i32 = 3;
+/// Ball speed multiplier for each stack of the [`PowerUp::SlowBall`]
+const SLOW_BALL_MULTIPLIER: f32 = 0.75;
+/// Maximum number of the [`PowerUp::SlowBall`] stacks that affect balls
+const SLOW_BALL_MAX_STACKS: i32 = 3;
+/// Number of balls added by the [`PowerUp::MultiBall`]
+const MULTI_BALL_COUNT: u32 = 2;
+pub const LASER_BOLT_RADIUS: f32 = 1.0;
+
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActivePowerUps>();
+        app.add_systems(OnEnter(InLevel), power_ups_reset);
+        app.add_systems(
+            FixedUpdate,
+            (power_ups_tick, power_ups_apply)
+                .chain()
+                .before(PhysicsSet::Movement)
+                .run_if(in_state(GameState::InGame)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            (power_ups_fall, laser_fire, laser_movement)
+                .in_set(PhysicsSet::Movement)
+                .run_if(in_state(GameState::InGame)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            (power_ups_drop, power_ups_catch, laser_collision)
+                .in_set(PhysicsSet::CollisionResolution)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUp {
+    WidePlatform,
+    SlowBall,
+    ExtraLife,
+    MultiBall,
+    StickyPlatform,
+    Laser,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 6] = [
+        PowerUp::WidePlatform,
+        PowerUp::SlowBall,
+        PowerUp::ExtraLife,
+        PowerUp::MultiBall,
+        PowerUp::StickyPlatform,
+        PowerUp::Laser,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUp::WidePlatform => "Wide",
+            PowerUp::SlowBall => "Slow",
+            PowerUp::ExtraLife => "Life",
+            PowerUp::MultiBall => "Multi",
+            PowerUp::StickyPlatform => "Sticky",
+            PowerUp::Laser => "Laser",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUp::WidePlatform => Color::srgb_u8(0x48, 0x95, 0xef),
+            PowerUp::SlowBall => Color::srgb_u8(0x4c, 0xc9, 0xf0),
+            PowerUp::ExtraLife => Color::srgb_u8(0xe6, 0x39, 0x46),
+            PowerUp::MultiBall => Color::srgb_u8(0xf7, 0x25, 0x85),
+            PowerUp::StickyPlatform => Color::srgb_u8(0x90, 0xbe, 0x6d),
+            PowerUp::Laser => Color::srgb_u8(0xff, 0x00, 0x6e),
+        }
+    }
+}
+
+/// Falling capsule which applies the power-up when caught by the platform
+#[derive(Component, Debug)]
+pub struct PowerUpCapsule {
+    pub power_up: PowerUp,
+}
+
+#[derive(Component, Debug)]
+pub struct LaserBolt;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ActivePowerUp {
+    pub power_up: PowerUp,
+    /// Time in seconds until the power-up expires
+    pub remaining: f32,
+}
+
+/// Timed power-ups currently in effect. Catching the same power-up
+/// again adds a new stack with its own duration.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivePowerUps {
+    pub active: Vec<ActivePowerUp>,
+    /// Time in seconds until the next laser shot
+    pub laser_cooldown: f32,
+}
+
+impl ActivePowerUps {
+    pub fn stacks(&self, power_up: PowerUp) -> usize {
+        self.active
+            .iter()
+            .filter(|active| active.power_up == power_up)
+            .count()
+    }
+
+    /// Time until the last stack of the power-up expires
+    pub fn remaining(&self, power_up: PowerUp) -> Option<f32> {
+        self.active
+            .iter()
+            .filter(|active| active.power_up == power_up)
+            .map(|active| active.remaining)
+            .reduce(f32::max)
+    }
+}
+
+/// Power-ups end with the level, unless the level is continued from the [`RestoredRun`]
+fn power_ups_reset(
+    config: Res<GameConfig>,
+    restored: Option<Res<RestoredRun>>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    mut commands: Commands,
+) {
+    let Some(restored) = restored else {
+        *active_power_ups = ActivePowerUps::default();
+        return;
+    };
+    *active_power_ups = restored.0.power_ups.clone();
+    for (position, power_up) in restored.0.capsules.iter() {
+        spawn_capsule(&config, &mut commands, *position, *power_up);
+    }
+    for position in restored.0.laser_bolts.iter() {
+        spawn_laser_bolt(&mut commands, *position);
+    }
+}
+
+fn spawn_capsule(config: &GameConfig, commands: &mut Commands, position: Vec2, power_up: PowerUp) {
+    let transform = Transform::from_translation(position.extend(0.0));
+    commands
+        .spawn(TransformBundle::from_transform(transform))
+        .insert(StateScoped(InLevel))
+        .insert(Sensor {
+            width: config.power_up_width,
+            height: config.power_up_height,
+        })
+        .insert(Interpolated::new(transform.translation))
+        .insert(PowerUpCapsule { power_up });
+}
+
+fn spawn_laser_bolt(commands: &mut Commands, position: Vec2) {
+    let transform = Transform::from_translation(position.extend(0.0));
+    commands
+        .spawn(TransformBundle::from_transform(transform))
+        .insert(StateScoped(InLevel))
+        .insert(Ball {
+            radius: LASER_BOLT_RADIUS,
+        })
+        .insert(Dynamic)
+        .insert(Interpolated::new(transform.translation))
+        .insert(LaserBolt);
+}
+
+fn power_ups_tick(time: Res<Time>, mut active_power_ups: ResMut<ActivePowerUps>) {
+    let dt = time.delta_seconds();
+    for active in active_power_ups.active.iter_mut() {
+        active.remaining -= dt;
+    }
+    active_power_ups
+        .active
+        .retain(|active| 0.0 < active.remaining);
+}
+
+/// Effects are derived from the active power-ups every step,
+/// so expired power-ups do not leave anything behind.
+fn power_ups_apply(
+    config: Res<GameConfig>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut platform: Query<(&mut GamePlatform, &mut Rectangle, &mut Transform)>,
+    mut balls: Query<&mut GameBall>,
+) {
+    let wide_stacks =
+        (active_power_ups.stacks(PowerUp::WidePlatform) as i32).min(WIDE_PLATFORM_MAX_STACKS);
+    let width_mul = WIDE_PLATFORM_MULTIPLIER.powi(wide_stacks);
+    if let Ok((mut platform, mut rect, mut transform)) = platform.get_single_mut() {
+        platform.width = config.platform_width * width_mul;
+        platform.sticky = active_power_ups.stacks(PowerUp::StickyPlatform) != 0;
+        rect.width = platform.width;
+        transform.scale.x = width_mul;
+    }
+
+    let slow_stacks = (active_power_ups.stacks(PowerUp::SlowBall) as i32).min(SLOW_BALL_MAX_STACKS);
+    let speed = config.ball_speed * SLOW_BALL_MULTIPLIER.powi(slow_stacks);
+    for mut ball in balls.iter_mut() {
+        ball.speed = speed;
+    }
+}
+
+fn power_ups_drop(
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut brick_destroyed_events: EventReader<BrickDestroyedEvent>,
+    mut commands: Commands,
+) {
+    for event in brick_destroyed_events.read() {
+        if config.power_up_drop_chance <= rng.0.gen::<f32>() {
+            continue;
+        }
+        let power_up = PowerUp::ALL[rng.0.gen_range(0..PowerUp::ALL.len())];
+        spawn_capsule(&config, &mut commands, event.position, power_up);
+    }
+}
+
+fn power_ups_fall(
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut commands: Commands,
+    mut capsules: Query<(Entity, &mut Transform), With<PowerUpCapsule>>,
+) {
+    for (entity, mut transform) in capsules.iter_mut() {
+        transform.translation.y -= config.power_up_fall_speed * time.delta_seconds();
+        if transform.translation.y < 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn power_ups_catch(
+    config: Res<GameConfig>,
+    mut commands: Commands,
+    mut sensor_events: EventReader<SensorEvent>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    mut lifes: ResMut<PlatformLifes>,
+    mut ball_events: EventWriter<GameBallEvent>,
+    mut gameplay_events: EventWriter<GameplayEvents>,
+    capsules: Query<&PowerUpCapsule>,
+    platform: Query<(&Transform, &GamePlatform)>,
+) {
+    for event in sensor_events.read() {
+        let (Ok(capsule), Ok((platform_transform, platform))) =
+            (capsules.get(event.sensor), platform.get(event.entity))
+        else {
+            continue;
+        };
+        commands.entity(event.sensor).despawn_recursive();
+        gameplay_events.send(GameplayEvents::PowerUpCaught);
+
+        match capsule.power_up {
+            PowerUp::ExtraLife => {
+                lifes.current += 1;
+                lifes.max = lifes.max.max(lifes.current);
+            }
+            PowerUp::MultiBall => {
+                let position = platform_transform.translation.truncate()
+                    + Vec2::Y * (platform.height * 0.5 + config.ball_radius * 2.0);
+                for i in 0..MULTI_BALL_COUNT {
+                    let x = i as f32 / (MULTI_BALL_COUNT - 1).max(1) as f32 - 0.5;
+                    ball_events.send(GameBallEvent::Spawn {
+                        position,
+                        direction: Vec2::new(x, 1.0),
+                    });
+                }
+            }
+            // Other power-ups stay active for the `power_up_duration`
+            power_up => {
+                active_power_ups.active.push(ActivePowerUp {
+                    power_up,
+                    remaining: config.power_up_duration,
+                });
+            }
+        }
+    }
+}
+
+fn laser_fire(
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    mut commands: Commands,
+    platform: Query<(&Transform, &GamePlatform)>,
+) {
+    if active_power_ups.stacks(PowerUp::Laser) == 0 {
+        return;
+    }
+    active_power_ups.laser_cooldown -= time.delta_seconds();
+    if 0.0 < active_power_ups.laser_cooldown {
+        return;
+    }
+    active_power_ups.laser_cooldown = 1.0 / config.laser_fire_rate;
+
+    let Ok((platform_transform, platform)) = platform.get_single() else {
+        return;
+    };
+    // One bolt from each side of the platform
+    for side in [-1.0, 1.0] {
+        let position = platform_transform.translation.truncate()
+            + Vec2::new(
+                side * (platform.width / 2.0 - LASER_BOLT_RADIUS),
+                platform.height / 2.0 + LASER_BOLT_RADIUS,
+            );
+        spawn_laser_bolt(&mut commands, position);
+    }
+}
+
+fn laser_movement(
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut commands: Commands,
+    mut bolts: Query<(Entity, &mut Transform), With<LaserBolt>>,
+) {
+    for (entity, mut transform) in bolts.iter_mut() {
+        transform.translation.y += config.laser_bolt_speed * time.delta_seconds();
+        if config.scene_height < transform.translation.y {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Bolts disappear after the first hit, the hit brick is damaged by the `bricks_collision`
+fn laser_collision(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    bolts: Query<(), With<LaserBolt>>,
+) {
+    // A bolt can hit several rectangles during the same step
+    let mut despawned = Vec::new();
+    for event in collision_events.read() {
+        if bolts.contains(event.entity1) && !despawned.contains(&event.entity1) {
+            commands.entity(event.entity1).despawn_recursive();
+            despawned.push(event.entity1);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::game::bricks::Score;
+use crate::game::input::{PlayerInput, PlayerInputSet};
+use crate::game::level::CurrentLevel;
+use crate::game::GameState;
+use crate::high_scores::{date, unix_timestamp};
+use crate::storage::{read_ron, write_ron_compact, DataDir, StorageError};
+
+/// Speed of the replay while seeking
+const SEEK_SPEED: f64 = 64.0;
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 8.0;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataDir>();
+        app.add_systems(
+            FixedUpdate,
+            replay_record
+                .after(PlayerInputSet)
+                .run_if(in_state(GameState::InGame))
+                .run_if(resource_exists::<ReplayRecorder>),
+        );
+        app.add_systems(
+            FixedUpdate,
+            replay_playback
+                .in_set(PlayerInputSet)
+                .run_if(in_state(GameState::InGame))
+                .run_if(resource_exists::<ReplayPlayback>),
+        );
+
+        // Recording stops with the run, whether it ended or was left
+        app.add_systems(OnEnter(GameState::EndGame), replay_save);
+        app.add_systems(OnEnter(GameState::NotInGame), replay_save);
+
+        app.add_systems(
+            OnEnter(GameState::EndGame),
+            playback_finish.run_if(resource_exists::<ReplayPlayback>),
+        );
+        // Playback goes through the level transitions without waiting for the player
+        app.add_systems(
+            Update,
+            playback_continue
+                .run_if(in_state(GameState::LevelComplete).or_else(in_state(GameState::NotInGame)))
+                .run_if(resource_exists::<ReplayPlayback>),
+        );
+    }
+}
+
+/// Recorded run. Starting a run with the same `seed` and feeding it the same
+/// `inputs` every fixed step repeats the run exactly, as long as the game
+/// config and levels did not change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub tick_rate: f64,
+    /// Seconds since the UNIX epoch
+    pub timestamp: u64,
+    /// Score at the end of the recording
+    pub score: u32,
+    /// Level the recording ended on, starting from 1
+    pub level: u32,
+    /// Input of every step. Consecutive equal inputs are stored once with their count.
+    pub inputs: Vec<(u32, PlayerInput)>,
+}
+
+impl Replay {
+    pub const VERSION: u32 = 1;
+    /// Older replays are removed when a new one is saved
+    pub const MAX_REPLAYS: usize = 20;
+    const EXTENSION: &'static str = "replay.ron";
+
+    const DIR_NAME: &'static str = "replays";
+
+    /// Number of recorded steps
+    pub fn ticks(&self) -> u32 {
+        self.inputs.iter().map(|(count, _)| count).sum()
+    }
+
+    /// Recording length in seconds
+    pub fn duration(&self) -> f64 {
+        self.ticks() as f64 / self.tick_rate
+    }
+
+    /// Date of the recording in the `YYYY-MM-DD` format
+    pub fn date(&self) -> String {
+        date(self.timestamp)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, StorageError> {
+        read_ron(path)
+    }
+
+    /// Saves the replay next to the previous ones and removes the oldest if there are too many
+    pub fn save(&self, data_dir: &DataDir) {
+        let Some(dir) = data_dir.join(Self::DIR_NAME) else {
+            warn!("No data directory, replay is not saved");
+            return;
+        };
+        let path = dir.join(format!("{}.{}", self.timestamp, Self::EXTENSION));
+        if let Err(e) = write_ron_compact(&path, self) {
+            error!("Could not save replay to {}: {}", path.display(), e);
+            return;
+        }
+
+        let mut paths = Self::paths(&dir);
+        if Self::MAX_REPLAYS < paths.len() {
+            for path in paths.drain(..paths.len() - Self::MAX_REPLAYS) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    error!("Could not remove old replay {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Replay files from the oldest to the newest
+    fn paths(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(Self::EXTENSION))
+            })
+            .collect();
+        // File names start with the timestamp
+        paths.sort();
+        paths
+    }
+
+    /// Saved replays from the newest to the oldest. Broken files are skipped.
+    pub fn list(data_dir: &DataDir) -> Vec<Replay> {
+        let Some(dir) = data_dir.join(Self::DIR_NAME) else {
+            return Vec::new();
+        };
+        Self::paths(&dir)
+            .iter()
+            .rev()
+            .filter_map(|path| match Self::load(path) {
+                Ok(replay) => replay,
+                Err(e) => {
+                    error!("Could not load replay {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Records the input of the current run. Inserted when a run starts,
+/// except for the runs continued from a save or played from a replay.
+#[derive(Resource, Debug)]
+pub struct ReplayRecorder {
+    seed: u64,
+    inputs: Vec<(u32, PlayerInput)>,
+}
+
+impl ReplayRecorder {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, input: PlayerInput) {
+        match self.inputs.last_mut() {
+            Some((count, last)) if *last == input => *count += 1,
+            _ => self.inputs.push((1, input)),
+        }
+    }
+
+    pub fn replay(&self, tick_rate: f64, score: u32, level: u32) -> Replay {
+        Replay {
+            version: Replay::VERSION,
+            seed: self.seed,
+            tick_rate,
+            timestamp: unix_timestamp(),
+            score,
+            level,
+            inputs: self.inputs.clone(),
+        }
+    }
+}
+
+/// Replay being played. The run is driven by the recorded input instead of the player.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    /// Steps played since the start of the run
+    tick: u32,
+    /// Position in the `replay.inputs`
+    run: usize,
+    run_tick: u32,
+    /// Step to fast forward to
+    seek: Option<u32>,
+    paused: bool,
+    speed: f64,
+    /// The recording is over, the last state stays on screen
+    finished: bool,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            tick: 0,
+            run: 0,
+            run_tick: 0,
+            seek: None,
+            paused: false,
+            speed: 1.0,
+            finished: false,
+        }
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn seeking(&self) -> bool {
+        self.seek.is_some()
+    }
+
+    /// Goes back to the first step. The run itself is restarted by leaving the game.
+    pub fn restart(&mut self) {
+        self.tick = 0;
+        self.run = 0;
+        self.run_tick = 0;
+        self.finished = false;
+    }
+
+    fn next_input(&mut self) -> Option<PlayerInput> {
+        let (count, input) = *self.replay.inputs.get(self.run)?;
+        self.tick += 1;
+        self.run_tick += 1;
+        if count <= self.run_tick {
+            self.run += 1;
+            self.run_tick = 0;
+        }
+        Some(input)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(MIN_SPEED);
+    }
+
+    /// Starts fast forwarding to the `tick`. Returns `true` if the tick
+    /// was already played and the run has to be restarted to reach it.
+    pub fn seek(&mut self, tick: u32) -> bool {
+        let tick = tick.min(self.replay.ticks());
+        let restart = tick < self.tick;
+        let start = if restart { 0 } else { self.tick };
+        self.seek = (start < tick).then_some(tick);
+        restart
+    }
+
+    /// Sets the virtual time speed for the playback. Fixed steps follow
+    /// the virtual time, so this controls how fast the replay goes.
+    pub fn apply_time(&self, time: &mut Time<Virtual>) {
+        if self.seek.is_some() {
+            time.set_relative_speed_f64(SEEK_SPEED);
+            time.unpause();
+        } else {
+            time.set_relative_speed_f64(self.speed);
+            if self.paused || self.finished {
+                time.pause();
+            } else {
+                time.unpause();
+            }
+        }
+    }
+}
+
+fn replay_record(input: Res<PlayerInput>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.push(*input);
+}
+
+fn replay_save(
+    config: Res<GameConfig>,
+    data_dir: Res<DataDir>,
+    score: Option<Res<Score>>,
+    current_level: Option<Res<CurrentLevel>>,
+    recorder: Option<Res<ReplayRecorder>>,
+    mut commands: Commands,
+) {
+    let Some(recorder) = recorder else {
+        return;
+    };
+    commands.remove_resource::<ReplayRecorder>();
+    if recorder.inputs.is_empty() {
+        return;
+    }
+    let replay = recorder.replay(
+        config.physics_tick_rate,
+        score.map_or(0, |score| score.score),
+        current_level.map_or(1, |level| level.index as u32 + 1),
+    );
+    replay.save(&data_dir);
+}
+
+fn replay_playback(
+    mut input: ResMut<PlayerInput>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    *input = playback.next_input().unwrap_or_default();
+
+    let seek_done = playback.seek.is_some_and(|seek| seek <= playback.tick);
+    let input_over = playback.replay.ticks() <= playback.tick;
+    if !seek_done && !input_over {
+        return;
+    }
+    if seek_done {
+        playback.seek = None;
+    }
+    if input_over {
+        playback.finished = true;
+    }
+    playback.apply_time(&mut virtual_time);
+    // Steps left in this frame would run past the point
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+}
+
+fn playback_finish(
+    score: Res<Score>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    playback.finished = true;
+    playback.seek = None;
+    playback.apply_time(&mut time);
+    if score.score == playback.replay.score {
+        info!("Replay finished with the recorded score {}", score.score);
+    } else {
+        warn!(
+            "Replay finished with score {}, but {} was recorded",
+            score.score, playback.replay.score
+        );
+    }
+}
+
+fn playback_continue(mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(GameState::InGame);
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::events::GameplayEvents;
+use crate::game::bricks::{BrickHitEvent, Score};
+use crate::game::physics::PhysicsSet;
+use crate::game::platform::PlatformLifes;
+use crate::game::snapshot::RestoredRun;
+use crate::game::{GameState, InLevel};
+
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScoreEvent>();
+        app.init_resource::<Combo>();
+        app.add_systems(OnEnter(InLevel), combo_reset);
+        // Hits of the step that clears the level are counted before the level ends
+        app.add_systems(
+            FixedUpdate,
+            score_update
+                .after(PhysicsSet::CollisionResolution)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+/// Points added to the [`Score`] at the `position`. The score goes up
+/// by the `points` times the `multiplier`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScoreEvent {
+    pub position: Vec2,
+    pub points: u32,
+    pub multiplier: u32,
+}
+
+/// Brick hits in a row without any ball touching the platform
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Combo {
+    pub hits: u32,
+}
+
+impl Combo {
+    /// Multiplier of the points for the next hit
+    pub fn multiplier(&self, config: &GameConfig) -> u32 {
+        (1 + self.hits / config.combo_hits_per_multiplier).min(config.combo_max_multiplier)
+    }
+}
+
+/// Points of a brick hit before the combo multiplier. Faster balls add
+/// a part of the brick `points` on top.
+pub fn hit_points(config: &GameConfig, points: u32, speed_mul: f32) -> u32 {
+    let bonus = points as f32 * config.speed_bonus * (speed_mul - 1.0).max(0.0);
+    points + bonus.round() as u32
+}
+
+/// Combo ends with the level, unless the level is continued from the [`RestoredRun`]
+fn combo_reset(restored: Option<Res<RestoredRun>>, mut combo: ResMut<Combo>) {
+    *combo = restored.map_or_else(Combo::default, |restored| restored.0.combo.clone());
+}
+
+fn score_update(
+    config: Res<GameConfig>,
+    lifes: Res<PlatformLifes>,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut gameplay_events: EventReader<GameplayEvents>,
+    mut brick_hit_events: EventReader<BrickHitEvent>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    let mut level_cleared = false;
+    for event in gameplay_events.read() {
+        match event {
+            GameplayEvents::PlatformBounce | GameplayEvents::LifeLost => combo.hits = 0,
+            GameplayEvents::LevelCleared => level_cleared = true,
+            _ => {}
+        }
+    }
+
+    for hit in brick_hit_events.read() {
+        let points = hit_points(&config, hit.points, hit.speed_mul);
+        let multiplier = combo.multiplier(&config);
+        score.score += points * multiplier;
+        combo.hits += 1;
+        score_events.send(ScoreEvent {
+            position: hit.position,
+            points,
+            multiplier,
+        });
+    }
+
+    // Each life left is worth the `life_bonus`
+    if level_cleared {
+        score.score += config.life_bonus * lifes.current;
+        score_events.send(ScoreEvent {
+            position: Vec2::new(config.scene_width, config.scene_height) / 2.0,
+            points: config.life_bonus,
+            multiplier: lifes.current,
+        });
+    }
+}
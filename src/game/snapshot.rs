@@ -0,0 +1,189 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::ball::{BallSnapshot, GameBall};
+use crate::game::bricks::{BrickPath, BrickSnapshot, BricksCount, GameBrick, Score};
+use crate::game::level::CurrentLevel;
+use crate::game::physics::{Interpolated, Rectangle};
+use crate::game::platform::{GamePlatform, PlatformLifes};
+use crate::game::power_ups::{ActivePowerUps, LaserBolt, PowerUp, PowerUpCapsule};
+use crate::game::replay::ReplayPlayback;
+use crate::game::scoring::Combo;
+use crate::game::{GameRng, InLevel};
+use crate::storage::{read_ron, write_ron, DataDir};
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataDir>();
+        app.add_event::<SaveRunEvent>();
+        // Runs after the `Update`, so the level is still alive
+        // even if the state is changed by the same frame. The `AppExit` of a closed
+        // window is sent in the `PostUpdate`, and the app quits after the frame.
+        app.add_systems(
+            Last,
+            snapshot_save
+                .run_if(in_state(InLevel))
+                .run_if(not(resource_exists::<ReplayPlayback>))
+                .run_if(on_event::<SaveRunEvent>().or_else(on_event::<AppExit>())),
+        );
+        app.add_systems(
+            Update,
+            snapshot_restored
+                .run_if(in_state(InLevel))
+                .run_if(resource_exists::<RestoredRun>),
+        );
+    }
+}
+
+/// Requests to save the current run before leaving it
+#[derive(Event, Debug)]
+pub struct SaveRunEvent;
+
+/// Everything needed to continue the run from the moment it was saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub level: usize,
+    pub score: Score,
+    /// Missing in runs saved before combos were added
+    #[serde(default)]
+    pub combo: Combo,
+    pub lifes: PlatformLifes,
+    pub bricks_count: BricksCount,
+    pub rng: GameRng,
+    pub platform: Vec2,
+    pub balls: Vec<BallSnapshot>,
+    pub bricks: Vec<BrickSnapshot>,
+    pub power_ups: ActivePowerUps,
+    pub capsules: Vec<(Vec2, PowerUp)>,
+    pub laser_bolts: Vec<Vec2>,
+}
+
+impl RunSnapshot {
+    const FILE_NAME: &'static str = "run.ron";
+
+    pub fn exists(data_dir: &DataDir) -> bool {
+        data_dir
+            .join(Self::FILE_NAME)
+            .is_some_and(|path| path.exists())
+    }
+
+    pub fn load(data_dir: &DataDir) -> Option<Self> {
+        let path = data_dir.join(Self::FILE_NAME)?;
+        match read_ron(&path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                error!("Could not load saved run from {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, data_dir: &DataDir) {
+        let Some(path) = data_dir.join(Self::FILE_NAME) else {
+            warn!("No data directory, run is not saved");
+            return;
+        };
+        if let Err(e) = write_ron(&path, self) {
+            error!("Could not save run to {}: {}", path.display(), e);
+        }
+    }
+
+    /// Removes the saved run, so it can be continued only once
+    pub fn remove(data_dir: &DataDir) {
+        let Some(path) = data_dir.join(Self::FILE_NAME) else {
+            return;
+        };
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => error!("Could not remove saved run {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Run being continued. Spawn systems of the level use it instead of
+/// the level data, and it is removed once the level is entered.
+#[derive(Resource, Debug, Clone)]
+pub struct RestoredRun(pub RunSnapshot);
+
+/// Position used by the physics, which is ahead of the rendered one
+fn position(transform: &Transform, interpolated: Option<&Interpolated>) -> Vec2 {
+    interpolated
+        .map_or(transform.translation, |interpolated| interpolated.current)
+        .truncate()
+}
+
+fn snapshot_save(
+    data_dir: Res<DataDir>,
+    current_level: Res<CurrentLevel>,
+    score: Res<Score>,
+    combo: Res<Combo>,
+    lifes: Res<PlatformLifes>,
+    bricks_count: Res<BricksCount>,
+    rng: Res<GameRng>,
+    active_power_ups: Res<ActivePowerUps>,
+    platform: Query<(&Transform, Option<&Interpolated>), With<GamePlatform>>,
+    balls: Query<(&Transform, Option<&Interpolated>, &GameBall)>,
+    bricks: Query<(
+        &Transform,
+        Option<&Interpolated>,
+        &Rectangle,
+        &GameBrick,
+        Option<&BrickPath>,
+    )>,
+    capsules: Query<(&Transform, Option<&Interpolated>, &PowerUpCapsule)>,
+    bolts: Query<(&Transform, Option<&Interpolated>), With<LaserBolt>>,
+) {
+    let snapshot = RunSnapshot {
+        level: current_level.index,
+        score: score.clone(),
+        combo: combo.clone(),
+        lifes: lifes.clone(),
+        bricks_count: bricks_count.clone(),
+        rng: rng.clone(),
+        platform: platform
+            .get_single()
+            .map(|(transform, interpolated)| position(transform, interpolated))
+            .unwrap_or_default(),
+        balls: balls
+            .iter()
+            .map(|(transform, interpolated, ball)| BallSnapshot {
+                position: position(transform, interpolated),
+                velocity: ball.velocity,
+                speed_mul: ball.speed_mul,
+                state: ball.state,
+                attach_offset: ball.attach_offset,
+            })
+            .collect(),
+        bricks: bricks
+            .iter()
+            .map(
+                |(transform, interpolated, rect, brick, path)| BrickSnapshot {
+                    position: position(transform, interpolated),
+                    size: Vec2::new(rect.width, rect.height),
+                    brick: brick.clone(),
+                    path: path.cloned(),
+                },
+            )
+            .collect(),
+        power_ups: active_power_ups.clone(),
+        capsules: capsules
+            .iter()
+            .map(|(transform, interpolated, capsule)| {
+                (position(transform, interpolated), capsule.power_up)
+            })
+            .collect(),
+        laser_bolts: bolts
+            .iter()
+            .map(|(transform, interpolated)| position(transform, interpolated))
+            .collect(),
+    };
+    snapshot.save(&data_dir);
+}
+
+fn snapshot_restored(mut commands: Commands) {
+    commands.remove_resource::<RestoredRun>();
+}
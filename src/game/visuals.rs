@@ -0,0 +1,397 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::config::GameConfig;
+use crate::game::ball::{ball_color, GameBall};
+use crate::game::ball_trail::BallTrailPlugin;
+use crate::game::bricks::{BrickDestroyedEvent, BrickHitEvent, GameBrick};
+use crate::game::camera_effects::CameraEffectsPlugin;
+use crate::game::particles::ParticlesPlugin;
+use crate::game::physics::Rectangle;
+use crate::game::platform::GamePlatform;
+use crate::game::power_ups::{LaserBolt, PowerUp, PowerUpCapsule, LASER_BOLT_RADIUS};
+use crate::game::scene::SceneBorder;
+use crate::game::InLevel;
+
+/// Part of the color multi-hit bricks lose when almost destroyed
+const MULTI_HIT_DARKEN: f32 = 0.7;
+/// Time in seconds a brick flashes white after a hit
+const BRICK_FLASH_DURATION: f32 = 0.12;
+/// Flash fades out in steps, each of them has its own material
+const BRICK_FLASH_STEPS: u8 = 4;
+/// Time in seconds a destroyed brick takes to shrink away
+const BRICK_BREAK_DURATION: f32 = 0.2;
+/// Turn in radians of a destroyed brick while it shrinks
+const BRICK_BREAK_ROLL: f32 = 0.4;
+
+/// Meshes, materials and lights of the game entities. The simulation spawns
+/// entities with a transform and gameplay components only, and this plugin
+/// dresses them up, so the game can run without it in tests.
+pub struct VisualsPlugin;
+
+impl Plugin for VisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((ParticlesPlugin, CameraEffectsPlugin, BallTrailPlugin));
+        app.init_resource::<BrickAssets>();
+        app.add_systems(
+            Update,
+            (
+                scene_visuals,
+                platform_visuals,
+                (ball_visuals, ball_color_update).chain(),
+                (bricks_visuals, bricks_flash, bricks_look_update).chain(),
+                (bricks_break_spawn, bricks_break_update)
+                    .chain()
+                    .run_if(in_state(InLevel)),
+                capsule_visuals,
+                laser_bolt_visuals,
+            ),
+        );
+    }
+}
+
+/// Bricks of the same size share a mesh. Materials come from a palette keyed
+/// by the [`BrickLook`], so each brick shows its own state without
+/// a material of its own.
+#[derive(Resource, Debug, Default)]
+struct BrickAssets {
+    meshes: HashMap<[u32; 2], Handle<Mesh>>,
+    materials: HashMap<BrickLook, Handle<StandardMaterial>>,
+}
+
+/// Everything the brick material depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BrickLook {
+    color: [u8; 4],
+    health: u32,
+    max_health: u32,
+    /// Flash step from 0 without flash to the [`BRICK_FLASH_STEPS`] fully white
+    flash: u8,
+}
+
+impl BrickLook {
+    fn new(brick: &GameBrick, flash: u8) -> Self {
+        Self {
+            color: brick.color().to_srgba().to_u8_array(),
+            health: brick.health(),
+            max_health: brick.max_health(),
+            flash,
+        }
+    }
+
+    /// Damaged bricks glow darker, a flash mixes in white
+    fn color(&self) -> Color {
+        let [r, g, b, a] = self.color;
+        let mut color = Color::srgba_u8(r, g, b, a);
+        if 0 < self.health && self.health < self.max_health {
+            let damage = 1.0 - self.health as f32 / self.max_health as f32;
+            color = color.mix(&Color::BLACK, damage * MULTI_HIT_DARKEN);
+        }
+        color.mix(&Color::WHITE, self.flash as f32 / BRICK_FLASH_STEPS as f32)
+    }
+}
+
+/// Time left of the white flash after a hit
+#[derive(Component, Debug, Default)]
+struct BrickFlash(f32);
+
+impl BrickFlash {
+    fn step(&self) -> u8 {
+        (self.0 / BRICK_FLASH_DURATION * BRICK_FLASH_STEPS as f32).ceil() as u8
+    }
+}
+
+/// Destroyed brick shrinking away after the brick itself is gone
+#[derive(Component, Debug)]
+struct BrickBreak {
+    look: BrickLook,
+    age: f32,
+}
+
+impl BrickAssets {
+    fn mesh(&mut self, meshes: &mut Assets<Mesh>, size: Vec2) -> Handle<Mesh> {
+        self.meshes
+            .entry([size.x.to_bits(), size.y.to_bits()])
+            .or_insert_with(|| meshes.add(Mesh::from(Cuboid::new(size.x, size.y, 1.0))))
+            .clone()
+    }
+
+    fn material(
+        &mut self,
+        materials: &mut Assets<StandardMaterial>,
+        look: BrickLook,
+    ) -> Handle<StandardMaterial> {
+        self.materials
+            .entry(look)
+            .or_insert_with(|| {
+                materials.add(StandardMaterial {
+                    emissive: look.color().into(),
+                    ..default()
+                })
+            })
+            .clone()
+    }
+}
+
+fn scene_visuals(
+    config: Res<GameConfig>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    borders: Query<(Entity, &Rectangle), Added<SceneBorder>>,
+) {
+    if borders.is_empty() {
+        return;
+    }
+    let material = materials.add(StandardMaterial {
+        emissive: config.scene_border_color.into(),
+        ..default()
+    });
+    for (entity, rect) in borders.iter() {
+        commands.entity(entity).insert((
+            meshes.add(Mesh::from(Cuboid::new(rect.width, rect.height, 1.0))),
+            material.clone(),
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+fn platform_visuals(
+    config: Res<GameConfig>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    platform: Query<Entity, Added<GamePlatform>>,
+) {
+    // Wider platform is scaled by the transform, the mesh keeps the base width
+    for entity in platform.iter() {
+        commands.entity(entity).insert((
+            meshes.add(Mesh::from(Cuboid::new(
+                config.platform_width,
+                config.platform_height,
+                1.0,
+            ))),
+            materials.add(StandardMaterial {
+                emissive: config.platform_color.into(),
+                ..default()
+            }),
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+/// Each ball has its own material and light, which change color with its speed
+fn ball_visuals(
+    config: Res<GameConfig>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    balls: Query<(Entity, &GameBall), Added<GameBall>>,
+) {
+    for (entity, ball) in balls.iter() {
+        let color = ball_color(&config, ball.speed_mul);
+        commands
+            .entity(entity)
+            .insert((
+                meshes.add(Mesh::from(Sphere {
+                    radius: ball.radius,
+                })),
+                materials.add(StandardMaterial {
+                    emissive: color.into(),
+                    ..default()
+                }),
+                VisibilityBundle::default(),
+            ))
+            .with_children(|parent| {
+                parent.spawn(PointLightBundle {
+                    point_light: PointLight {
+                        color,
+                        intensity: 1000.0,
+                        range: 1000.0,
+                        radius: 1000.0,
+                        ..default()
+                    },
+                    ..default()
+                });
+            });
+    }
+}
+
+fn ball_color_update(
+    config: Res<GameConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    balls: Query<(&GameBall, &Handle<StandardMaterial>, &Children), Changed<GameBall>>,
+    mut lights: Query<&mut PointLight>,
+) {
+    for (ball, material, children) in balls.iter() {
+        let color = ball_color(&config, ball.speed_mul);
+        for child in children.iter() {
+            let Ok(mut light) = lights.get_mut(*child) else {
+                continue;
+            };
+            // Ball changes every step, the material is touched only on a new color
+            if light.color == color {
+                continue;
+            }
+            light.color = color;
+            if let Some(material) = materials.get_mut(material) {
+                material.emissive = color.into();
+            }
+        }
+    }
+}
+
+fn bricks_visuals(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut brick_assets: ResMut<BrickAssets>,
+    bricks: Query<(Entity, &GameBrick, &Rectangle), Added<GameBrick>>,
+) {
+    for (entity, brick, rect) in bricks.iter() {
+        commands.entity(entity).insert((
+            brick_assets.mesh(&mut meshes, Vec2::new(rect.width, rect.height)),
+            brick_assets.material(&mut materials, BrickLook::new(brick, 0)),
+            VisibilityBundle::default(),
+            BrickFlash::default(),
+        ));
+    }
+}
+
+fn bricks_flash(
+    time: Res<Time>,
+    mut brick_hit_events: EventReader<BrickHitEvent>,
+    mut flashes: Query<&mut BrickFlash>,
+) {
+    for mut flash in flashes.iter_mut() {
+        if 0.0 < flash.0 {
+            flash.0 = (flash.0 - time.delta_seconds()).max(0.0);
+        }
+    }
+    // Destroyed bricks are already gone and flash as they break instead
+    for event in brick_hit_events.read() {
+        if let Ok(mut flash) = flashes.get_mut(event.brick) {
+            flash.0 = BRICK_FLASH_DURATION;
+        }
+    }
+}
+
+/// Swaps the brick material for the one of its health and flash
+fn bricks_look_update(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut brick_assets: ResMut<BrickAssets>,
+    mut bricks: Query<
+        (&GameBrick, &BrickFlash, &mut Handle<StandardMaterial>),
+        Or<(Changed<GameBrick>, Changed<BrickFlash>)>,
+    >,
+) {
+    for (brick, flash, mut material) in bricks.iter_mut() {
+        let look = brick_assets.material(&mut materials, BrickLook::new(brick, flash.step()));
+        if *material != look {
+            *material = look;
+        }
+    }
+}
+
+fn bricks_break_spawn(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut brick_assets: ResMut<BrickAssets>,
+    mut brick_destroyed_events: EventReader<BrickDestroyedEvent>,
+) {
+    for event in brick_destroyed_events.read() {
+        let look = BrickLook {
+            color: event.color.to_srgba().to_u8_array(),
+            health: 0,
+            max_health: 0,
+            flash: BRICK_FLASH_STEPS,
+        };
+        commands
+            .spawn(PbrBundle {
+                mesh: brick_assets.mesh(&mut meshes, event.size),
+                material: brick_assets.material(&mut materials, look),
+                transform: Transform::from_translation(event.position.extend(0.0)),
+                ..default()
+            })
+            .insert(StateScoped(InLevel))
+            .insert(BrickBreak { look, age: 0.0 });
+    }
+}
+
+/// Destroyed bricks fade from white to their color while shrinking and turning
+fn bricks_break_update(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut brick_assets: ResMut<BrickAssets>,
+    mut breaks: Query<(
+        Entity,
+        &mut BrickBreak,
+        &mut Transform,
+        &mut Handle<StandardMaterial>,
+    )>,
+) {
+    for (entity, mut brick_break, mut transform, mut material) in breaks.iter_mut() {
+        brick_break.age += time.delta_seconds();
+        if BRICK_BREAK_DURATION <= brick_break.age {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let left = 1.0 - brick_break.age / BRICK_BREAK_DURATION;
+        transform.scale = Vec3::splat(left);
+        transform.rotation = Quat::from_rotation_z((1.0 - left) * BRICK_BREAK_ROLL);
+
+        let flash = (left * BRICK_FLASH_STEPS as f32).ceil() as u8;
+        if flash != brick_break.look.flash {
+            brick_break.look.flash = flash;
+            *material = brick_assets.material(&mut materials, brick_break.look);
+        }
+    }
+}
+
+fn capsule_visuals(
+    config: Res<GameConfig>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    capsules: Query<(Entity, &PowerUpCapsule), Added<PowerUpCapsule>>,
+) {
+    for (entity, capsule) in capsules.iter() {
+        commands.entity(entity).insert((
+            meshes.add(
+                Mesh::from(Capsule3d::new(
+                    config.power_up_height / 2.0,
+                    (config.power_up_width - config.power_up_height).max(0.0),
+                ))
+                // Capsule mesh is vertical by default
+                .rotated_by(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+            ),
+            materials.add(StandardMaterial {
+                emissive: capsule.power_up.color().into(),
+                ..default()
+            }),
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+fn laser_bolt_visuals(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bolts: Query<Entity, Added<LaserBolt>>,
+) {
+    for entity in bolts.iter() {
+        commands.entity(entity).insert((
+            meshes.add(Mesh::from(Sphere {
+                radius: LASER_BOLT_RADIUS,
+            })),
+            materials.add(StandardMaterial {
+                emissive: PowerUp::Laser.color().into(),
+                ..default()
+            }),
+            VisibilityBundle::default(),
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{read_ron, write_ron, DataDir};
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataDir>();
+        app.add_systems(PreStartup, setup_high_scores);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+    /// Level the run ended on, starting from 1
+    pub level: u32,
+    /// Seconds since the UNIX epoch
+    pub timestamp: u64,
+}
+
+impl HighScore {
+    pub fn new(initials: String, score: u32, level: u32) -> Self {
+        Self {
+            initials,
+            score,
+            level,
+            timestamp: unix_timestamp(),
+        }
+    }
+
+    /// Date of the record in the `YYYY-MM-DD` format
+    pub fn date(&self) -> String {
+        date(self.timestamp)
+    }
+}
+
+/// Seconds since the UNIX epoch
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Date of the UNIX `timestamp` in the `YYYY-MM-DD` format
+pub fn date(timestamp: u64) -> String {
+    // Days to civil date conversion from
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Best scores sorted from the highest to the lowest
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub const MAX_ENTRIES: usize = 10;
+    pub const INITIALS_LENGTH: usize = 3;
+    const FILE_NAME: &'static str = "high_scores.ron";
+
+    /// Whether the score is high enough to be put into the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        0 < score
+            && (self.entries.len() < Self::MAX_ENTRIES
+                || self.entries.last().is_some_and(|last| last.score < score))
+    }
+
+    pub fn insert(&mut self, high_score: HighScore) {
+        let position = self
+            .entries
+            .partition_point(|entry| high_score.score <= entry.score);
+        self.entries.insert(position, high_score);
+        self.entries.truncate(Self::MAX_ENTRIES);
+    }
+
+    /// Loads high scores from the data directory. Missing or
+    /// broken file results in an empty table.
+    pub fn load(data_dir: &DataDir) -> Self {
+        let Some(path) = data_dir.join(Self::FILE_NAME) else {
+            warn!("Could not find data directory, high scores are not loaded");
+            return Self::default();
+        };
+        match read_ron::<Self>(&path) {
+            Ok(Some(mut high_scores)) => {
+                high_scores
+                    .entries
+                    .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+                high_scores.entries.truncate(Self::MAX_ENTRIES);
+                high_scores
+            }
+            Ok(None) => Self::default(),
+            Err(e) => {
+                warn!("Could not load high scores from {path:?}: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes high scores into the data directory.
+    pub fn save(&self, data_dir: &DataDir) {
+        let Some(path) = data_dir.join(Self::FILE_NAME) else {
+            warn!("Could not find data directory, high scores are not saved");
+            return;
+        };
+        if let Err(e) = write_ron(&path, self) {
+            warn!("Could not save high scores to {path:?}: {e}");
+        }
+    }
+}
+
+fn setup_high_scores(data_dir: Res<DataDir>, mut commands: Commands) {
+    commands.insert_resource(HighScores::load(&data_dir));
+}
@@ -0,0 +1,7 @@
+pub mod audio;
+pub mod config;
+pub mod events;
+pub mod game;
+pub mod high_scores;
+pub mod storage;
+pub mod ui;
@@ -3,15 +3,13 @@ use bevy::{
     window::{PresentMode, WindowMode},
 };
 
-mod config;
-mod events;
-mod game;
-mod ui;
-
-use config::ConfigPlugin;
-use events::EventsPlugin;
-use game::GamePlugin;
-use ui::UiPlugin;
+use bevyout::audio::AudioPlugin;
+use bevyout::config::ConfigPlugin;
+use bevyout::events::EventsPlugin;
+use bevyout::game::visuals::VisualsPlugin;
+use bevyout::game::GamePlugin;
+use bevyout::high_scores::HighScoresPlugin;
+use bevyout::ui::UiPlugin;
 
 fn main() {
     let mut app = App::new();
@@ -28,9 +26,12 @@ fn main() {
             ..Default::default()
         }),
         ConfigPlugin,
+        AudioPlugin,
         UiPlugin,
         GamePlugin,
+        VisualsPlugin,
         EventsPlugin,
+        HighScoresPlugin,
     ));
 
     app.run();
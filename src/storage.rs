@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// Directory where user settings are stored
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("bevyout"))
+}
+
+/// Directory where user data like high scores, the saved run and replays
+/// is stored. Nothing is stored if it is `None`. Defaults to the platform
+/// data directory, tests insert their own before adding the plugins.
+#[derive(Resource, Debug, Clone)]
+pub struct DataDir(pub Option<PathBuf>);
+
+impl Default for DataDir {
+    fn default() -> Self {
+        Self(dirs::data_dir().map(|dir| dir.join("bevyout")))
+    }
+}
+
+impl DataDir {
+    /// Path of the file or directory inside the data directory
+    pub fn join(&self, path: impl AsRef<Path>) -> Option<PathBuf> {
+        self.0.as_ref().map(|dir| dir.join(path))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("{0}")]
+    Serialize(#[from] ron::Error),
+}
+
+/// Reads a RON file. Missing file is not an error and results in `None`.
+pub fn read_ron<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Some(ron::from_str(&content)?))
+}
+
+/// Writes a RON file, creating parent directories if needed.
+pub fn write_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let content = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    write(path, &content)
+}
+
+/// Writes a RON file without any formatting. Used for large files
+/// which are not meant to be edited by hand.
+pub fn write_ron_compact<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let content = ron::ser::to_string(value)?;
+    write(path, &content)
+}
+
+fn write(path: &Path, content: &str) -> Result<(), StorageError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
@@ -1,17 +1,15 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 
+use crate::game::input::WorldCursor;
+
 pub struct CursorPlugin;
 
 impl Plugin for CursorPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(WorldCursor(Vec3::ZERO));
         app.add_systems(Update, world_cursor_system);
     }
 }
 
-#[derive(Resource, Debug, Clone)]
-pub struct WorldCursor(pub Vec3);
-
 fn world_cursor_system(
     mut crs: ResMut<WorldCursor>,
     windows: Query<&Window, With<PrimaryWindow>>,
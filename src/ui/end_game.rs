@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 
 use crate::config::UiConfig;
+use crate::game::bricks::Score;
+use crate::game::level::CurrentLevel;
 use crate::game::GameState;
+use crate::high_scores::HighScores;
+use crate::ui::high_scores::{spawn_name_entry, NameEntry};
 use crate::ui::{spawn_button, UiState};
 
 pub struct EndGamePlugin;
@@ -19,18 +23,38 @@ enum EndGameButton {
     BackToMainMenu,
 }
 
-fn end_game_setup(mut commands: Commands, config: Res<UiConfig>) {
+fn end_game_setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    score: Res<Score>,
+    current_level: Res<CurrentLevel>,
+    high_scores: Res<HighScores>,
+) {
+    let name_entry = NameEntry::new(&high_scores, score.score, current_level.index as u32 + 1);
     commands
         .spawn(NodeBundle {
             style: config.menu_style.clone(),
             background_color: config.menu_color.into(),
             ..default()
         })
-        .insert(StateScoped(UiState::MainMenu))
+        .insert(StateScoped(UiState::EndGame))
         .with_children(|builder| {
+            builder.spawn(TextBundle {
+                text: Text::from_section(
+                    format!("Score: {}", score.score),
+                    config.text_style.clone(),
+                ),
+                ..default()
+            });
+            if name_entry.is_some() {
+                spawn_name_entry(builder, &config);
+            }
             spawn_button(builder, &config, EndGameButton::Retry);
             spawn_button(builder, &config, EndGameButton::BackToMainMenu);
         });
+    if let Some(name_entry) = name_entry {
+        commands.insert_resource(name_entry);
+    }
 }
 
 fn button_system(
@@ -0,0 +1,204 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use crate::config::UiConfig;
+use crate::high_scores::{HighScore, HighScores};
+use crate::storage::DataDir;
+use crate::ui::{spawn_button, UiState};
+
+pub struct HighScoresMenuPlugin;
+
+impl Plugin for HighScoresMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::HighScores), high_scores_setup);
+        app.add_systems(Update, button_system.run_if(in_state(UiState::HighScores)));
+
+        app.add_systems(
+            Update,
+            (name_entry_input, name_entry_update)
+                .chain()
+                .distributive_run_if(in_state(UiState::EndGame).or_else(in_state(UiState::Victory)))
+                .distributive_run_if(resource_exists::<NameEntry>),
+        );
+        app.add_systems(OnExit(UiState::EndGame), name_entry_submit);
+        app.add_systems(OnExit(UiState::Victory), name_entry_submit);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum HighScoresButton {
+    Back,
+}
+
+/// Score waiting for the player to enter initials
+#[derive(Resource, Debug, Clone)]
+pub struct NameEntry {
+    pub initials: String,
+    pub score: u32,
+    pub level: u32,
+}
+
+impl NameEntry {
+    /// Returns new entry if the score qualifies for the high scores table
+    pub fn new(high_scores: &HighScores, score: u32, level: u32) -> Option<Self> {
+        high_scores.qualifies(score).then(|| Self {
+            initials: String::new(),
+            score,
+            level,
+        })
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiNameEntry;
+
+/// Spawns a prompt for the initials. The text is updated while [`NameEntry`] exists.
+pub fn spawn_name_entry(child_builder: &mut ChildBuilder, config: &UiConfig) {
+    child_builder
+        .spawn(TextBundle {
+            text: Text::from_section("", config.text_style.clone()),
+            ..default()
+        })
+        .insert(UiNameEntry);
+}
+
+fn name_entry_input(
+    data_dir: Res<DataDir>,
+    mut commands: Commands,
+    mut name_entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut ui_name_entry: Query<&mut Text, With<UiNameEntry>>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(c) => {
+                for c in c.chars().filter(char::is_ascii_alphanumeric) {
+                    if name_entry.initials.len() < HighScores::INITIALS_LENGTH {
+                        name_entry.initials.push(c.to_ascii_uppercase());
+                    }
+                }
+            }
+            Key::Backspace => {
+                name_entry.initials.pop();
+            }
+            Key::Enter if !name_entry.initials.is_empty() => {
+                high_scores.insert(HighScore::new(
+                    name_entry.initials.clone(),
+                    name_entry.score,
+                    name_entry.level,
+                ));
+                high_scores.save(&data_dir);
+                commands.remove_resource::<NameEntry>();
+
+                if let Ok(mut text) = ui_name_entry.get_single_mut() {
+                    text.sections[0].value = format!("Saved as {}", name_entry.initials);
+                }
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn name_entry_update(
+    name_entry: Res<NameEntry>,
+    mut ui_name_entry: Query<&mut Text, With<UiNameEntry>>,
+) {
+    if !name_entry.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = ui_name_entry.get_single_mut() {
+        let str = format!(
+            "New high score! Initials: {:_<width$}",
+            name_entry.initials,
+            width = HighScores::INITIALS_LENGTH
+        );
+        text.sections[0].value = str;
+    }
+}
+
+/// Records the score even if the player left the screen without entering initials
+fn name_entry_submit(
+    data_dir: Res<DataDir>,
+    mut commands: Commands,
+    name_entry: Option<Res<NameEntry>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(name_entry) = name_entry else {
+        return;
+    };
+    let initials = if name_entry.initials.is_empty() {
+        "???".to_string()
+    } else {
+        name_entry.initials.clone()
+    };
+    high_scores.insert(HighScore::new(initials, name_entry.score, name_entry.level));
+    high_scores.save(&data_dir);
+    commands.remove_resource::<NameEntry>();
+}
+
+fn high_scores_setup(mut commands: Commands, config: Res<UiConfig>, high_scores: Res<HighScores>) {
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::HighScores))
+        .with_children(|builder| {
+            if high_scores.entries.is_empty() {
+                builder.spawn(TextBundle {
+                    text: Text::from_section("No high scores yet", config.text_style.clone()),
+                    ..default()
+                });
+            }
+            for (i, entry) in high_scores.entries.iter().enumerate() {
+                builder.spawn(TextBundle {
+                    text: Text::from_section(
+                        format!(
+                            "{:>2}. {:<3} {:>6} Level {:<2} {}",
+                            i + 1,
+                            entry.initials,
+                            entry.score,
+                            entry.level,
+                            entry.date()
+                        ),
+                        config.text_style.clone(),
+                    ),
+                    ..default()
+                });
+            }
+            spawn_button(builder, &config, HighScoresButton::Back);
+        });
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut interaction_query: Query<
+        (&HighScoresButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                match button {
+                    HighScoresButton::Back => ui_state.set(UiState::MainMenu),
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 
-use crate::config::UiConfig;
+use crate::config::{GameConfig, UiConfig};
 use crate::game::bricks::Score;
 use crate::game::platform::PlatformLifes;
+use crate::game::power_ups::{ActivePowerUps, PowerUp};
+use crate::game::scoring::Combo;
 use crate::ui::UiState;
 
 pub struct HudPlugin;
@@ -10,7 +12,10 @@ pub struct HudPlugin;
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(UiState::InGame), hud_setup);
-        app.add_systems(Update, hud_update.run_if(in_state(UiState::InGame)));
+        app.add_systems(
+            Update,
+            (hud_update, hud_combo_update, hud_power_ups_update).run_if(in_state(UiState::InGame)),
+        );
     }
 }
 
@@ -20,6 +25,12 @@ struct UiLifesCount;
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct UiScore;
 
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiCombo;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiPowerUps;
+
 fn hud_setup(mut command: Commands, config: Res<UiConfig>) {
     command
         .spawn(NodeBundle {
@@ -33,7 +44,7 @@ fn hud_setup(mut command: Commands, config: Res<UiConfig>) {
             background_color: Color::NONE.into(),
             ..default()
         })
-        .insert(StateScoped(UiState::MainMenu))
+        .insert(StateScoped(UiState::InGame))
         .with_children(|builder| {
             // lifes count
             builder
@@ -58,6 +69,22 @@ fn hud_setup(mut command: Commands, config: Res<UiConfig>) {
                     ..default()
                 })
                 .insert(UiScore);
+
+            // combo, empty without one
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section("", config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiCombo);
+
+            // active power-ups
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section("", config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiPowerUps);
         });
 }
 
@@ -75,3 +102,37 @@ fn hud_update(
     let str = format!("Score: {}", score.score);
     text.sections[0].value = str;
 }
+
+fn hud_combo_update(
+    config: Res<GameConfig>,
+    combo: Res<Combo>,
+    mut ui_combo: Query<&mut Text, With<UiCombo>>,
+) {
+    let mut text = ui_combo.single_mut();
+    text.sections[0].value = if combo.hits == 0 {
+        String::new()
+    } else {
+        format!("Combo: {} ×{}", combo.hits, combo.multiplier(&config))
+    };
+}
+
+fn hud_power_ups_update(
+    active_power_ups: Res<ActivePowerUps>,
+    mut ui_power_ups: Query<&mut Text, With<UiPowerUps>>,
+) {
+    let mut text = ui_power_ups.single_mut();
+    let str = PowerUp::ALL
+        .iter()
+        .filter_map(|power_up| {
+            let remaining = active_power_ups.remaining(*power_up)?;
+            let stacks = active_power_ups.stacks(*power_up);
+            Some(if stacks == 1 {
+                format!("{} {:.1}s", power_up.name(), remaining)
+            } else {
+                format!("{} x{} {:.1}s", power_up.name(), stacks, remaining)
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.sections[0].value = str;
+}
@@ -0,0 +1,166 @@
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+use crate::config::{Action, GameSettings, UiConfig};
+use crate::events::SettingsEvents;
+use crate::ui::{spawn_button, UiState};
+
+pub struct KeyBindingsPlugin;
+
+impl Plugin for KeyBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::KeyBindings), key_bindings_setup);
+        app.add_systems(OnExit(UiState::KeyBindings), key_bindings_cleanup);
+        app.add_systems(
+            Update,
+            (button_system, key_bindings_input, key_bindings_update)
+                .chain()
+                .run_if(in_state(UiState::KeyBindings)),
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum KeyBindingsButton {
+    Rebind(Action),
+    Back,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiKeyBinding(Action);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiKeyBindingsStatus;
+
+/// State of the key bindings screen
+#[derive(Resource, Debug, Clone, Default)]
+struct KeyBindingsMenu {
+    /// Action waiting for a new key
+    waiting: Option<Action>,
+    status: String,
+}
+
+fn key_bindings_setup(mut commands: Commands, config: Res<UiConfig>) {
+    commands.init_resource::<KeyBindingsMenu>();
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::KeyBindings))
+        .with_children(|builder| {
+            for action in Action::ALL {
+                builder
+                    .spawn(ButtonBundle {
+                        style: config.btn_style.clone(),
+                        background_color: config.btn_color_normal.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn(TextBundle {
+                                text: Text::from_section("", config.text_style.clone()),
+                                ..default()
+                            })
+                            .insert(UiKeyBinding(action));
+                    })
+                    .insert(KeyBindingsButton::Rebind(action));
+            }
+
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section("", config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiKeyBindingsStatus);
+
+            spawn_button(builder, &config, KeyBindingsButton::Back);
+        });
+}
+
+fn key_bindings_cleanup(mut commands: Commands) {
+    commands.remove_resource::<KeyBindingsMenu>();
+}
+
+/// Binds the first pressed key to the action waiting for it
+fn key_bindings_input(
+    settings: Res<GameSettings>,
+    mut menu: ResMut<KeyBindingsMenu>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut settings_events: EventWriter<SettingsEvents>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some(action) = menu.waiting else {
+            continue;
+        };
+        match settings.key_bindings.action(event.key_code) {
+            Some(bound) if bound != action => {
+                menu.status = format!("{:?} is already used by {:?}", event.key_code, bound);
+            }
+            _ => {
+                settings_events.send(SettingsEvents::Rebind(action, event.key_code));
+                menu.waiting = None;
+                menu.status.clear();
+            }
+        }
+    }
+}
+
+fn key_bindings_update(
+    settings: Res<GameSettings>,
+    menu: Res<KeyBindingsMenu>,
+    mut ui_bindings: Query<(&UiKeyBinding, &mut Text), Without<UiKeyBindingsStatus>>,
+    mut ui_status: Query<&mut Text, With<UiKeyBindingsStatus>>,
+) {
+    for (binding, mut text) in ui_bindings.iter_mut() {
+        let str = if menu.waiting == Some(binding.0) {
+            format!("{:?}: press a key", binding.0)
+        } else {
+            format!(
+                "{:?}: {:?}",
+                binding.0,
+                settings.key_bindings.key(binding.0)
+            )
+        };
+        text.sections[0].value = str;
+    }
+
+    let mut text = ui_status.single_mut();
+    text.sections[0].value.clone_from(&menu.status);
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut menu: ResMut<KeyBindingsMenu>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut interaction_query: Query<
+        (&KeyBindingsButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                match button {
+                    KeyBindingsButton::Rebind(action) => {
+                        menu.waiting = Some(*action);
+                        menu.status.clear();
+                    }
+                    KeyBindingsButton::Back => ui_state.set(UiState::Settings),
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::config::UiConfig;
+use crate::game::bricks::Score;
+use crate::game::level::{CampaignLevels, CurrentLevel, LevelError, LevelStatus};
+use crate::game::GameState;
+use crate::ui::{spawn_button, UiState};
+
+pub struct LevelCompletePlugin;
+
+impl Plugin for LevelCompletePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::LevelComplete), level_complete_setup);
+        app.add_systems(
+            Update,
+            button_system.run_if(in_state(UiState::LevelComplete)),
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LevelCompleteButton {
+    NextLevel,
+    BackToMainMenu,
+}
+
+fn level_complete_setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    current_level: Res<CurrentLevel>,
+    score: Res<Score>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::LevelComplete))
+        .with_children(|builder| {
+            builder.spawn(TextBundle {
+                text: Text::from_section(
+                    format!("Level {} complete", current_level.index + 1),
+                    config.text_style.clone(),
+                ),
+                ..default()
+            });
+            builder.spawn(TextBundle {
+                text: Text::from_section(
+                    format!("Score: {}", score.score),
+                    config.text_style.clone(),
+                ),
+                ..default()
+            });
+            spawn_button(builder, &config, LevelCompleteButton::NextLevel);
+            spawn_button(builder, &config, LevelCompleteButton::BackToMainMenu);
+        });
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    current_level: Res<CurrentLevel>,
+    campaign_levels: CampaignLevels,
+    mut commands: Commands,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&LevelCompleteButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                match button {
+                    // Level is advanced when the screen is left
+                    LevelCompleteButton::NextLevel => {
+                        match campaign_levels.status(current_level.index + 1) {
+                            LevelStatus::Ready => {
+                                ui_state.set(UiState::InGame);
+                                game_state.set(GameState::InGame);
+                            }
+                            LevelStatus::Loading => {
+                                info!("Next level is still loading");
+                            }
+                            LevelStatus::Failed => {
+                                let message = format!(
+                                    "Level #{} could not be loaded",
+                                    current_level.index + 2
+                                );
+                                error!("{}", message);
+                                commands.insert_resource(LevelError(message));
+                                ui_state.set(UiState::MainMenu);
+                                game_state.set(GameState::NotInGame);
+                            }
+                        }
+                    }
+                    LevelCompleteButton::BackToMainMenu => {
+                        ui_state.set(UiState::MainMenu);
+                        game_state.set(GameState::NotInGame);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
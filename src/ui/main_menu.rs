@@ -1,7 +1,10 @@
 use bevy::{app::AppExit, prelude::*};
 
 use crate::config::UiConfig;
+use crate::game::level::{CampaignLevels, LevelError, LevelStatus};
+use crate::game::snapshot::{RestoredRun, RunSnapshot};
 use crate::game::GameState;
+use crate::storage::DataDir;
 use crate::ui::{spawn_button, UiState};
 
 pub struct MainMenuPlugin;
@@ -9,18 +12,33 @@ pub struct MainMenuPlugin;
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(UiState::MainMenu), main_menu_setup);
-        app.add_systems(Update, button_system.run_if(in_state(UiState::MainMenu)));
+        app.add_systems(
+            Update,
+            (button_system, level_error_update).run_if(in_state(UiState::MainMenu)),
+        );
     }
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum MainMenuButton {
+    Continue,
     Start,
+    HighScores,
+    Replays,
     Settings,
     Exit,
 }
 
-fn main_menu_setup(mut commands: Commands, config: Res<UiConfig>) {
+/// Why the last run could not start or go on
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiLevelError;
+
+fn main_menu_setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    data_dir: Res<DataDir>,
+    level_error: Option<Res<LevelError>>,
+) {
     commands
         .spawn(NodeBundle {
             style: config.menu_style.clone(),
@@ -29,14 +47,43 @@ fn main_menu_setup(mut commands: Commands, config: Res<UiConfig>) {
         })
         .insert(StateScoped(UiState::MainMenu))
         .with_children(|builder| {
+            if RunSnapshot::exists(&data_dir) {
+                spawn_button(builder, &config, MainMenuButton::Continue);
+            }
             spawn_button(builder, &config, MainMenuButton::Start);
+            spawn_button(builder, &config, MainMenuButton::HighScores);
+            spawn_button(builder, &config, MainMenuButton::Replays);
             spawn_button(builder, &config, MainMenuButton::Settings);
             spawn_button(builder, &config, MainMenuButton::Exit);
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section(
+                        level_error.map_or_else(String::new, |error| error.0.clone()),
+                        config.text_style.clone(),
+                    ),
+                    ..default()
+                })
+                .insert(UiLevelError);
         });
 }
 
+fn level_error_update(
+    level_error: Option<Res<LevelError>>,
+    mut text: Query<&mut Text, With<UiLevelError>>,
+) {
+    let Some(level_error) = level_error.filter(|error| error.is_changed()) else {
+        return;
+    };
+    for mut text in text.iter_mut() {
+        text.sections[0].value = level_error.0.clone();
+    }
+}
+
 fn button_system(
     style: Res<UiConfig>,
+    data_dir: Res<DataDir>,
+    campaign_levels: CampaignLevels,
+    mut commands: Commands,
     mut ui_state: ResMut<NextState<UiState>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut interaction_query: Query<
@@ -50,10 +97,36 @@ fn button_system(
             Interaction::Pressed => {
                 *color = style.btn_color_pressed.into();
                 match button {
-                    MainMenuButton::Start => {
-                        ui_state.set(UiState::InGame);
-                        game_state.set(GameState::InGame);
+                    MainMenuButton::Continue => {
+                        // Broken save is reported by the `load` and the menu stays
+                        if let Some(snapshot) = RunSnapshot::load(&data_dir) {
+                            commands.remove_resource::<LevelError>();
+                            commands.insert_resource(RestoredRun(snapshot));
+                            ui_state.set(UiState::InGame);
+                            game_state.set(GameState::InGame);
+                        }
                     }
+                    // Campaign may still be loading right after the start
+                    MainMenuButton::Start => match campaign_levels.status(0) {
+                        LevelStatus::Ready => {
+                            commands.remove_resource::<LevelError>();
+                            ui_state.set(UiState::InGame);
+                            game_state.set(GameState::InGame);
+                        }
+                        LevelStatus::Loading => {
+                            commands.insert_resource(LevelError(
+                                "Levels are still loading".to_string(),
+                            ));
+                        }
+                        LevelStatus::Failed => {
+                            error!("First level of the campaign could not be loaded");
+                            commands.insert_resource(LevelError(
+                                "Level #1 could not be loaded".to_string(),
+                            ));
+                        }
+                    },
+                    MainMenuButton::HighScores => ui_state.set(UiState::HighScores),
+                    MainMenuButton::Replays => ui_state.set(UiState::Replays),
                     MainMenuButton::Settings => ui_state.set(UiState::Settings),
                     MainMenuButton::Exit => _ = exit.send(AppExit::Success),
                 }
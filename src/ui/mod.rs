@@ -2,18 +2,32 @@ use bevy::prelude::*;
 
 pub mod cursor;
 pub mod end_game;
+pub mod high_scores;
 pub mod hud;
+pub mod key_bindings;
+pub mod level_complete;
 pub mod main_menu;
 pub mod paused;
+pub mod replay;
+pub mod replays;
+pub mod score_popups;
 pub mod settings;
+pub mod victory;
 
 use crate::config::UiConfig;
 use cursor::CursorPlugin;
 use end_game::EndGamePlugin;
+use high_scores::HighScoresMenuPlugin;
 use hud::HudPlugin;
+use key_bindings::KeyBindingsPlugin;
+use level_complete::LevelCompletePlugin;
 use main_menu::MainMenuPlugin;
 use paused::PausedPlugin;
+use replay::ReplayControlsPlugin;
+use replays::ReplaysMenuPlugin;
+use score_popups::ScorePopupsPlugin;
 use settings::SettingsPlugin;
+use victory::VictoryPlugin;
 
 pub struct UiPlugin;
 
@@ -25,10 +39,17 @@ impl Plugin for UiPlugin {
         app.add_plugins((
             CursorPlugin,
             EndGamePlugin,
+            HighScoresMenuPlugin,
             HudPlugin,
+            KeyBindingsPlugin,
+            LevelCompletePlugin,
             MainMenuPlugin,
             PausedPlugin,
+            ReplayControlsPlugin,
+            ReplaysMenuPlugin,
+            ScorePopupsPlugin,
             SettingsPlugin,
+            VictoryPlugin,
         ));
     }
 }
@@ -41,7 +62,15 @@ pub enum UiState {
     InGame,
     Paused,
     Settings,
+    KeyBindings,
+    LevelComplete,
     EndGame,
+    Victory,
+    HighScores,
+    /// List of the recorded runs
+    Replays,
+    /// Controls over the replay being played
+    Replay,
 }
 
 fn spawn_button<B>(child_builder: &mut ChildBuilder, style: &UiConfig, button: B)
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::config::UiConfig;
+use crate::game::snapshot::SaveRunEvent;
 use crate::game::GameState;
 use crate::ui::{spawn_button, UiState};
 
@@ -43,6 +44,7 @@ fn button_system(
         (&PausedButton, &Interaction, &mut BackgroundColor),
         (Changed<Interaction>, With<Button>),
     >,
+    mut save_events: EventWriter<SaveRunEvent>,
 ) {
     for (button, interaction, mut color) in interaction_query.iter_mut() {
         match *interaction {
@@ -50,13 +52,14 @@ fn button_system(
                 *color = config.btn_color_pressed.into();
                 match button {
                     PausedButton::Resume => {
-                        // ui_state.pop().unwrap();
-                        // game_state.pop().unwrap();
+                        ui_state.set(UiState::InGame);
+                        game_state.set(GameState::InGame);
                     }
                     PausedButton::Settings => {
                         ui_state.set(UiState::Settings);
                     }
                     PausedButton::BackToMainMenu => {
+                        save_events.send(SaveRunEvent);
                         ui_state.set(UiState::MainMenu);
                         game_state.set(GameState::NotInGame);
                     }
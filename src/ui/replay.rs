@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+
+use crate::config::UiConfig;
+use crate::game::bricks::Score;
+use crate::game::replay::ReplayPlayback;
+use crate::game::GameState;
+use crate::ui::replays::format_duration;
+use crate::ui::{spawn_button, UiState};
+
+/// Step of the seek buttons in seconds
+const SEEK_STEP: f64 = 10.0;
+
+pub struct ReplayControlsPlugin;
+
+impl Plugin for ReplayControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::Replay), replay_setup);
+        app.add_systems(OnExit(UiState::Replay), replay_cleanup);
+        app.add_systems(
+            Update,
+            (button_system, replay_update)
+                .chain()
+                .run_if(in_state(UiState::Replay))
+                .run_if(resource_exists::<ReplayPlayback>),
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ReplayButton {
+    PlayPause,
+    Slower,
+    Faster,
+    SeekBack,
+    SeekForward,
+    Restart,
+    Exit,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiReplayStatus;
+
+fn replay_setup(mut commands: Commands, config: Res<UiConfig>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_self: AlignSelf::FlexEnd,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::Replay))
+        .with_children(|builder| {
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section("", config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiReplayStatus);
+
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    spawn_button(builder, &config, ReplayButton::PlayPause);
+                    spawn_button(builder, &config, ReplayButton::Slower);
+                    spawn_button(builder, &config, ReplayButton::Faster);
+                    spawn_button(builder, &config, ReplayButton::SeekBack);
+                    spawn_button(builder, &config, ReplayButton::SeekForward);
+                    spawn_button(builder, &config, ReplayButton::Restart);
+                    spawn_button(builder, &config, ReplayButton::Exit);
+                });
+        });
+}
+
+/// Playback ends with the screen, virtual time goes back to normal
+fn replay_cleanup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<ReplayPlayback>();
+    time.set_relative_speed(1.0);
+    time.unpause();
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut time: ResMut<Time<Virtual>>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&ReplayButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                let step = (SEEK_STEP * playback.replay.tick_rate) as u32;
+                let restart = match button {
+                    ReplayButton::PlayPause => {
+                        playback.toggle_pause();
+                        false
+                    }
+                    ReplayButton::Slower => {
+                        playback.slower();
+                        false
+                    }
+                    ReplayButton::Faster => {
+                        playback.faster();
+                        false
+                    }
+                    ReplayButton::SeekBack => {
+                        let tick = playback.tick().saturating_sub(step);
+                        playback.seek(tick)
+                    }
+                    ReplayButton::SeekForward => {
+                        let tick = playback.tick() + step;
+                        playback.seek(tick)
+                    }
+                    ReplayButton::Restart => playback.seek(0),
+                    ReplayButton::Exit => {
+                        ui_state.set(UiState::Replays);
+                        game_state.set(GameState::NotInGame);
+                        continue;
+                    }
+                };
+                // Played steps can not be undone, the run is started over
+                // and fast forwarded to the requested step.
+                if restart {
+                    game_state.set(GameState::NotInGame);
+                }
+                playback.apply_time(&mut time);
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
+
+fn replay_update(
+    playback: Res<ReplayPlayback>,
+    score: Option<Res<Score>>,
+    mut ui_status: Query<&mut Text, With<UiReplayStatus>>,
+) {
+    let tick_rate = playback.replay.tick_rate;
+    let state = if playback.seeking() {
+        "Seeking".to_string()
+    } else if playback.finished() {
+        "Finished".to_string()
+    } else if playback.paused() {
+        "Paused".to_string()
+    } else {
+        format!("x{}", playback.speed())
+    };
+    let mut text = ui_status.single_mut();
+    text.sections[0].value = format!(
+        "{} / {} {}  Score: {} / {}",
+        format_duration(playback.tick() as f64 / tick_rate),
+        format_duration(playback.replay.duration()),
+        state,
+        score.map_or(0, |score| score.score),
+        playback.replay.score,
+    );
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::config::{GameConfig, UiConfig};
+use crate::game::replay::{Replay, ReplayPlayback};
+use crate::game::GameState;
+use crate::storage::DataDir;
+use crate::ui::{spawn_button, UiState};
+
+pub struct ReplaysMenuPlugin;
+
+impl Plugin for ReplaysMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::Replays), replays_setup);
+        app.add_systems(OnExit(UiState::Replays), replays_cleanup);
+        app.add_systems(Update, button_system.run_if(in_state(UiState::Replays)));
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ReplaysButton {
+    /// Plays the replay with the index in the [`ReplaysMenu`]
+    Play(usize),
+    Back,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiReplaysStatus;
+
+/// Replays listed on the screen
+#[derive(Resource, Debug, Default)]
+struct ReplaysMenu {
+    replays: Vec<Replay>,
+}
+
+/// Time in the `MM:SS` format
+pub fn format_duration(seconds: f64) -> String {
+    let seconds = seconds as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn replays_setup(mut commands: Commands, config: Res<UiConfig>, data_dir: Res<DataDir>) {
+    let replays = Replay::list(&data_dir);
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::Replays))
+        .with_children(|builder| {
+            for (index, replay) in replays.iter().enumerate() {
+                builder
+                    .spawn(ButtonBundle {
+                        style: config.btn_style.clone(),
+                        background_color: config.btn_color_normal.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle {
+                            text: Text::from_section(
+                                format!(
+                                    "{} {} Level {} Score {}",
+                                    replay.date(),
+                                    format_duration(replay.duration()),
+                                    replay.level,
+                                    replay.score
+                                ),
+                                config.text_style.clone(),
+                            ),
+                            ..default()
+                        });
+                    })
+                    .insert(ReplaysButton::Play(index));
+            }
+
+            let status = if replays.is_empty() {
+                "No replays yet"
+            } else {
+                ""
+            };
+            builder
+                .spawn(TextBundle {
+                    text: Text::from_section(status, config.text_style.clone()),
+                    ..default()
+                })
+                .insert(UiReplaysStatus);
+
+            spawn_button(builder, &config, ReplaysButton::Back);
+        });
+    commands.insert_resource(ReplaysMenu { replays });
+}
+
+fn replays_cleanup(mut commands: Commands) {
+    commands.remove_resource::<ReplaysMenu>();
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    config: Res<GameConfig>,
+    menu: Res<ReplaysMenu>,
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&ReplaysButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut ui_status: Query<&mut Text, With<UiReplaysStatus>>,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                match button {
+                    ReplaysButton::Play(index) => {
+                        let replay = &menu.replays[*index];
+                        // Different step would give a different run
+                        let status = if replay.version != Replay::VERSION {
+                            Some(format!(
+                                "Replay version {} is not supported",
+                                replay.version
+                            ))
+                        } else if replay.tick_rate != config.physics_tick_rate {
+                            Some(format!(
+                                "Replay was recorded at {} ticks per second, the game runs at {}",
+                                replay.tick_rate, config.physics_tick_rate
+                            ))
+                        } else {
+                            None
+                        };
+                        if let Some(status) = status {
+                            ui_status.single_mut().sections[0].value = status;
+                            continue;
+                        }
+
+                        let playback = ReplayPlayback::new(replay.clone());
+                        playback.apply_time(&mut time);
+                        commands.insert_resource(playback);
+                        ui_state.set(UiState::Replay);
+                        game_state.set(GameState::InGame);
+                    }
+                    ReplaysButton::Back => ui_state.set(UiState::MainMenu),
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
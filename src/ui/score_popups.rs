@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::config::UiConfig;
+use crate::game::scoring::ScoreEvent;
+use crate::game::InLevel;
+
+/// Time in seconds a popup stays on the screen
+const POPUP_LIFETIME: f32 = 1.0;
+/// Speed in the scene units per second a popup floats up with
+const POPUP_RISE_SPEED: f32 = 20.0;
+const POPUP_FONT_SCALE: f32 = 0.6;
+
+pub struct ScorePopupsPlugin;
+
+impl Plugin for ScorePopupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (popups_spawn, popups_update)
+                .chain()
+                .run_if(in_state(InLevel)),
+        );
+    }
+}
+
+/// Text with the points scored, which floats up from the `position`
+/// in the scene and fades out
+#[derive(Component, Debug)]
+struct ScorePopup {
+    position: Vec3,
+    age: f32,
+}
+
+fn popups_spawn(
+    config: Res<UiConfig>,
+    mut commands: Commands,
+    mut score_events: EventReader<ScoreEvent>,
+) {
+    for event in score_events.read() {
+        let style = TextStyle {
+            font_size: config.text_style.font_size * POPUP_FONT_SCALE,
+            ..config.text_style.clone()
+        };
+        commands
+            .spawn(TextBundle {
+                text: Text::from_section(format!("+{} ×{}", event.points, event.multiplier), style),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                // Shown once placed by the `popups_update`
+                visibility: Visibility::Hidden,
+                ..default()
+            })
+            .insert(StateScoped(InLevel))
+            .insert(ScorePopup {
+                position: event.position.extend(0.0),
+                age: 0.0,
+            });
+    }
+}
+
+/// Moves popups from the scene to the screen, popups are despawned once faded out
+fn popups_update(
+    time: Res<Time>,
+    mut commands: Commands,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut popups: Query<(
+        Entity,
+        &mut ScorePopup,
+        &Node,
+        &mut Style,
+        &mut Text,
+        &mut Visibility,
+    )>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    for (entity, mut popup, node, mut style, mut text, mut visibility) in popups.iter_mut() {
+        popup.age += time.delta_seconds();
+        if POPUP_LIFETIME <= popup.age {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let position = popup.position + Vec3::Y * POPUP_RISE_SPEED * popup.age;
+        let Some(screen) = camera.world_to_viewport(camera_transform, position) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        // Centered over the point
+        let size = node.size();
+        style.left = Val::Px(screen.x - size.x / 2.0);
+        style.top = Val::Px(screen.y - size.y / 2.0);
+        *visibility = Visibility::Inherited;
+
+        let alpha = 1.0 - popup.age / POPUP_LIFETIME;
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::config::{GameSettings, UiConfig};
+use crate::config::{CameraMotion, GameSettings, ParticleDensity, UiConfig};
 use crate::events::SettingsEvents;
 use crate::game::GameState;
 use crate::ui::{spawn_button, UiState};
@@ -13,7 +13,15 @@ impl Plugin for SettingsPlugin {
 
         app.add_systems(
             Update,
-            (button_system, volume_update).run_if(in_state(UiState::Settings)),
+            (
+                button_system,
+                volume_update,
+                music_volume_update,
+                input_scheme_update,
+                particle_density_update,
+                camera_motion_update,
+            )
+                .run_if(in_state(UiState::Settings)),
         );
     }
 }
@@ -24,12 +32,36 @@ enum SettingsButton {
     DisplayWindowed,
     VolumeUp,
     VolumeDown,
+    MusicVolumeUp,
+    MusicVolumeDown,
+    InputKeyboard,
+    InputMouse,
+    InputGamepad,
+    ParticlesOff,
+    ParticlesLow,
+    ParticlesHigh,
+    MotionOff,
+    MotionReduced,
+    MotionFull,
+    KeyBindings,
     Back,
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct UiSettingsVolume;
 
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiSettingsMusicVolume;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiSettingsInputScheme;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiSettingsParticleDensity;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct UiSettingsCameraMotion;
+
 fn settings_setup(mut commands: Commands, config: Res<UiConfig>) {
     commands
         // Vertical layout
@@ -95,6 +127,117 @@ fn settings_setup(mut commands: Commands, config: Res<UiConfig>) {
                         })
                         .insert(UiSettingsVolume);
                 });
+            // Music
+            // Horizontal layout
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_auto_flow: GridAutoFlow::Row,
+                        margin: UiRect::all(Val::Auto),
+                        align_items: AlignItems::Center,
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                    background_color: config.menu_color.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    // Music volume up and down
+                    spawn_button(builder, &config, SettingsButton::MusicVolumeDown);
+                    spawn_button(builder, &config, SettingsButton::MusicVolumeUp);
+                    // Music volume value
+                    builder
+                        .spawn(TextBundle {
+                            text: Text::from_section(
+                                format!("Music: {}%", 100),
+                                config.text_style.clone(),
+                            ),
+                            ..default()
+                        })
+                        .insert(UiSettingsMusicVolume);
+                });
+            // Input
+            // Horizontal layout
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_auto_flow: GridAutoFlow::Row,
+                        margin: UiRect::all(Val::Auto),
+                        align_items: AlignItems::Center,
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                    background_color: config.menu_color.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    spawn_button(builder, &config, SettingsButton::InputKeyboard);
+                    spawn_button(builder, &config, SettingsButton::InputMouse);
+                    spawn_button(builder, &config, SettingsButton::InputGamepad);
+                    // Selected input scheme
+                    builder
+                        .spawn(TextBundle {
+                            text: Text::from_section("Input: ---", config.text_style.clone()),
+                            ..default()
+                        })
+                        .insert(UiSettingsInputScheme);
+                });
+            // Particles
+            // Horizontal layout
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_auto_flow: GridAutoFlow::Row,
+                        margin: UiRect::all(Val::Auto),
+                        align_items: AlignItems::Center,
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                    background_color: config.menu_color.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    spawn_button(builder, &config, SettingsButton::ParticlesOff);
+                    spawn_button(builder, &config, SettingsButton::ParticlesLow);
+                    spawn_button(builder, &config, SettingsButton::ParticlesHigh);
+                    // Selected particle density
+                    builder
+                        .spawn(TextBundle {
+                            text: Text::from_section("Particles: ---", config.text_style.clone()),
+                            ..default()
+                        })
+                        .insert(UiSettingsParticleDensity);
+                });
+            // Camera motion
+            // Horizontal layout
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_auto_flow: GridAutoFlow::Row,
+                        margin: UiRect::all(Val::Auto),
+                        align_items: AlignItems::Center,
+                        align_self: AlignSelf::Center,
+                        ..default()
+                    },
+                    background_color: config.menu_color.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    spawn_button(builder, &config, SettingsButton::MotionOff);
+                    spawn_button(builder, &config, SettingsButton::MotionReduced);
+                    spawn_button(builder, &config, SettingsButton::MotionFull);
+                    // Selected camera motion
+                    builder
+                        .spawn(TextBundle {
+                            text: Text::from_section("Motion: ---", config.text_style.clone()),
+                            ..default()
+                        })
+                        .insert(UiSettingsCameraMotion);
+                });
             // Back button
             builder
                 .spawn(NodeBundle {
@@ -110,6 +253,7 @@ fn settings_setup(mut commands: Commands, config: Res<UiConfig>) {
                     ..default()
                 })
                 .with_children(|builder| {
+                    spawn_button(builder, &config, SettingsButton::KeyBindings);
                     spawn_button(builder, &config, SettingsButton::Back);
                 });
         });
@@ -124,6 +268,42 @@ fn volume_update(
     text.sections[0].value = str;
 }
 
+fn music_volume_update(
+    settings: Res<GameSettings>,
+    mut volume: Query<&mut Text, With<UiSettingsMusicVolume>>,
+) {
+    let mut text = volume.single_mut();
+    let str = format!("Music: {}%", (settings.music_volume * 100.0) as u32);
+    text.sections[0].value = str;
+}
+
+fn input_scheme_update(
+    settings: Res<GameSettings>,
+    mut input_scheme: Query<&mut Text, With<UiSettingsInputScheme>>,
+) {
+    let mut text = input_scheme.single_mut();
+    let str = format!("Input: {:?}", settings.input_scheme);
+    text.sections[0].value = str;
+}
+
+fn particle_density_update(
+    settings: Res<GameSettings>,
+    mut particle_density: Query<&mut Text, With<UiSettingsParticleDensity>>,
+) {
+    let mut text = particle_density.single_mut();
+    let str = format!("Particles: {:?}", settings.particle_density);
+    text.sections[0].value = str;
+}
+
+fn camera_motion_update(
+    settings: Res<GameSettings>,
+    mut camera_motion: Query<&mut Text, With<UiSettingsCameraMotion>>,
+) {
+    let mut text = camera_motion.single_mut();
+    let str = format!("Motion: {:?}", settings.camera_motion);
+    text.sections[0].value = str;
+}
+
 fn button_system(
     style: Res<UiConfig>,
     game_state: Res<State<GameState>>,
@@ -162,6 +342,43 @@ fn button_system(
                     SettingsButton::VolumeDown => {
                         settings_events.send(SettingsEvents::VolumeDown);
                     }
+                    SettingsButton::MusicVolumeUp => {
+                        settings_events.send(SettingsEvents::MusicVolumeUp);
+                    }
+                    SettingsButton::MusicVolumeDown => {
+                        settings_events.send(SettingsEvents::MusicVolumeDown);
+                    }
+                    SettingsButton::InputKeyboard => {
+                        settings_events.send(SettingsEvents::InputKeyboard);
+                    }
+                    SettingsButton::InputMouse => {
+                        settings_events.send(SettingsEvents::InputMouse);
+                    }
+                    SettingsButton::InputGamepad => {
+                        settings_events.send(SettingsEvents::InputGamepad);
+                    }
+                    SettingsButton::ParticlesOff => {
+                        settings_events.send(SettingsEvents::ParticleDensity(ParticleDensity::Off));
+                    }
+                    SettingsButton::ParticlesLow => {
+                        settings_events.send(SettingsEvents::ParticleDensity(ParticleDensity::Low));
+                    }
+                    SettingsButton::ParticlesHigh => {
+                        settings_events
+                            .send(SettingsEvents::ParticleDensity(ParticleDensity::High));
+                    }
+                    SettingsButton::MotionOff => {
+                        settings_events.send(SettingsEvents::CameraMotion(CameraMotion::Off));
+                    }
+                    SettingsButton::MotionReduced => {
+                        settings_events.send(SettingsEvents::CameraMotion(CameraMotion::Reduced));
+                    }
+                    SettingsButton::MotionFull => {
+                        settings_events.send(SettingsEvents::CameraMotion(CameraMotion::Full));
+                    }
+                    SettingsButton::KeyBindings => {
+                        ui_state.set(UiState::KeyBindings);
+                    }
                 }
             }
             Interaction::Hovered => {
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use crate::config::UiConfig;
+use crate::game::bricks::Score;
+use crate::game::level::CurrentLevel;
+use crate::game::platform::PlatformLifes;
+use crate::game::GameState;
+use crate::high_scores::HighScores;
+use crate::ui::high_scores::{spawn_name_entry, NameEntry};
+use crate::ui::{spawn_button, UiState};
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(UiState::Victory), victory_setup);
+        app.add_systems(Update, button_system.run_if(in_state(UiState::Victory)));
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum VictoryButton {
+    PlayAgain,
+    BackToMainMenu,
+}
+
+fn victory_setup(
+    mut commands: Commands,
+    config: Res<UiConfig>,
+    score: Res<Score>,
+    platform_lifes: Res<PlatformLifes>,
+    current_level: Res<CurrentLevel>,
+    high_scores: Res<HighScores>,
+) {
+    let name_entry = NameEntry::new(&high_scores, score.score, current_level.index as u32 + 1);
+    commands
+        .spawn(NodeBundle {
+            style: config.menu_style.clone(),
+            background_color: config.menu_color.into(),
+            ..default()
+        })
+        .insert(StateScoped(UiState::Victory))
+        .with_children(|builder| {
+            builder.spawn(TextBundle {
+                text: Text::from_section("Victory!", config.text_style.clone()),
+                ..default()
+            });
+            builder.spawn(TextBundle {
+                text: Text::from_section(
+                    format!(
+                        "Score: {} Lifes left: {}",
+                        score.score, platform_lifes.current
+                    ),
+                    config.text_style.clone(),
+                ),
+                ..default()
+            });
+            if name_entry.is_some() {
+                spawn_name_entry(builder, &config);
+            }
+            spawn_button(builder, &config, VictoryButton::PlayAgain);
+            spawn_button(builder, &config, VictoryButton::BackToMainMenu);
+        });
+    if let Some(name_entry) = name_entry {
+        commands.insert_resource(name_entry);
+    }
+}
+
+fn button_system(
+    style: Res<UiConfig>,
+    mut ui_state: ResMut<NextState<UiState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut interaction_query: Query<
+        (&VictoryButton, &Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (button, interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = style.btn_color_pressed.into();
+                match button {
+                    VictoryButton::PlayAgain => {
+                        ui_state.set(UiState::InGame);
+                        game_state.set(GameState::InGame);
+                    }
+                    VictoryButton::BackToMainMenu => {
+                        ui_state.set(UiState::MainMenu);
+                        game_state.set(GameState::NotInGame);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = style.btn_color_hover.into();
+            }
+            Interaction::None => {
+                *color = style.btn_color_normal.into();
+            }
+        }
+    }
+}
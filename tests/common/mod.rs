@@ -0,0 +1,207 @@
+//! Headless game for the gameplay tests. Runs the [`GamePlugin`] without
+//! rendering or a window, one fixed step per frame.
+
+// Not every test file uses every helper
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use bevy::asset::AssetPlugin;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use bevyout::config::{GameConfig, GameSettings};
+use bevyout::events::EventsPlugin;
+use bevyout::game::input::WorldCursor;
+use bevyout::game::level::{BrickKind, Campaign, CampaignHandle, Level, LevelBrick};
+use bevyout::game::{GamePlugin, GameState};
+use bevyout::storage::DataDir;
+use bevyout::ui::UiState;
+
+/// Launch key of the default key bindings
+pub const LAUNCH: KeyCode = KeyCode::Space;
+pub const MOVE_LEFT: KeyCode = KeyCode::KeyA;
+pub const MOVE_RIGHT: KeyCode = KeyCode::KeyD;
+
+/// Number of games created by this test binary
+static GAMES: AtomicUsize = AtomicUsize::new(0);
+
+/// Normal brick with 1 health
+pub fn brick(x: f32, y: f32) -> LevelBrick {
+    LevelBrick {
+        position: Vec2::new(x, y),
+        size: Vec2::new(20.0, 8.0),
+        health: 1,
+        color: Color::WHITE,
+        kind: BrickKind::Normal,
+    }
+}
+
+pub fn level(bricks: Vec<LevelBrick>) -> Level {
+    Level {
+        name: "Test".to_string(),
+        bricks,
+    }
+}
+
+pub struct TestGame {
+    pub app: App,
+    /// Saved runs and replays of this game go here, not to the user data.
+    /// Removed when the game is dropped.
+    pub data_dir: PathBuf,
+}
+
+impl TestGame {
+    /// Game in the main menu with the `levels` as the campaign
+    pub fn new(config: GameConfig, levels: Vec<Level>) -> Self {
+        let data_dir = std::env::temp_dir().join(format!(
+            "bevyout-tests-{}-{}",
+            std::process::id(),
+            GAMES.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let step = Duration::from_secs_f64(1.0 / config.physics_tick_rate);
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            },
+        ));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(step));
+        app.insert_resource(Time::<Fixed>::from_duration(step));
+        app.insert_resource(config);
+        app.insert_resource(GameSettings::default());
+        app.insert_resource(DataDir(Some(data_dir.clone())));
+        app.init_state::<UiState>();
+        app.add_plugins((GamePlugin, EventsPlugin));
+
+        // Startup loads the campaign from the assets, it is replaced afterwards
+        app.update();
+        let levels = levels
+            .into_iter()
+            .map(|level| app.world_mut().resource_mut::<Assets<Level>>().add(level))
+            .collect();
+        let campaign = app
+            .world_mut()
+            .resource_mut::<Assets<Campaign>>()
+            .add(Campaign { levels });
+        app.insert_resource(CampaignHandle(campaign));
+
+        Self { app, data_dir }
+    }
+
+    /// Starts a new run like the main menu does
+    pub fn start(&mut self) {
+        self.set_state(GameState::InGame, UiState::InGame);
+    }
+
+    /// Goes to the states like the UI buttons do
+    pub fn set_state(&mut self, game: GameState, ui: UiState) {
+        let world = self.app.world_mut();
+        world.resource_mut::<NextState<GameState>>().set(game);
+        world.resource_mut::<NextState<UiState>>().set(ui);
+        self.step(1);
+    }
+
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Steps until `done` returns `true`, at most `max_frames`.
+    /// Returns `false` if it did not happen.
+    pub fn step_until(&mut self, max_frames: u32, done: impl Fn(&mut TestGame) -> bool) -> bool {
+        for _ in 0..max_frames {
+            self.app.update();
+            if done(self) {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn press(&mut self, key: KeyCode) {
+        self.key_input(key, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.key_input(key, ButtonState::Released);
+    }
+
+    fn key_input(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Moves the cursor, which the keyboard and mouse schemes aim with
+    pub fn aim(&mut self, target: Vec2) {
+        self.app.world_mut().resource_mut::<WorldCursor>().0 = target.extend(0.0);
+    }
+
+    /// Launches the attached balls towards the `target`. Takes two frames,
+    /// the press is picked up by the step of the next frame.
+    pub fn launch(&mut self, target: Vec2) {
+        self.aim(target);
+        self.press(LAUNCH);
+        self.step(1);
+        self.release(LAUNCH);
+        self.step(1);
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn game_state(&self) -> GameState {
+        *self.resource::<State<GameState>>().get()
+    }
+
+    pub fn ui_state(&self) -> UiState {
+        *self.resource::<State<UiState>>().get()
+    }
+
+    /// Number of entities with the component
+    pub fn count<C: Component>(&mut self) -> usize {
+        let world = self.app.world_mut();
+        world.query_filtered::<(), With<C>>().iter(world).count()
+    }
+
+    /// Translations of the entities with the component
+    pub fn positions<C: Component>(&mut self) -> Vec<Vec3> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&Transform, With<C>>()
+            .iter(world)
+            .map(|transform| transform.translation)
+            .collect()
+    }
+}
+
+impl Drop for TestGame {
+    fn drop(&mut self) {
+        match std::fs::remove_dir_all(&self.data_dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("Could not remove {}: {}", self.data_dir.display(), e),
+        }
+    }
+}
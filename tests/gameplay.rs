@@ -0,0 +1,266 @@
+mod common;
+
+use bevy::prelude::*;
+use bevyout::config::GameConfig;
+use bevyout::game::ball::GameBall;
+use bevyout::game::bricks::{BricksCount, GameBrick, Score};
+use bevyout::game::level::{BrickKind, Campaign, CampaignHandle, CurrentLevel, Level, LevelError};
+use bevyout::game::platform::{GamePlatform, PlatformLifes};
+use bevyout::game::replay::{ReplayPlayback, ReplayRecorder};
+use bevyout::game::scoring::Combo;
+use bevyout::game::snapshot::RunSnapshot;
+use bevyout::game::GameState;
+use bevyout::storage::DataDir;
+use bevyout::ui::UiState;
+
+use common::{brick, level, TestGame, MOVE_LEFT, MOVE_RIGHT};
+
+/// Platform starts in the middle of the scene, this is straight up from it
+const ABOVE_PLATFORM: Vec2 = Vec2::new(100.0, 300.0);
+
+fn config() -> GameConfig {
+    GameConfig {
+        power_up_drop_chance: 0.0,
+        ..default()
+    }
+}
+
+#[test]
+fn level_spawns_bricks() {
+    let mut indestructible = brick(20.0, 300.0);
+    indestructible.kind = BrickKind::Indestructible;
+    let mut game = TestGame::new(
+        config(),
+        vec![level(vec![
+            brick(100.0, 200.0),
+            brick(150.0, 200.0),
+            indestructible,
+        ])],
+    );
+    game.start();
+
+    assert_eq!(game.game_state(), GameState::InGame);
+    assert_eq!(game.count::<GameBrick>(), 3);
+    assert_eq!(game.count::<GameBall>(), 1);
+    assert_eq!(game.count::<GamePlatform>(), 1);
+    let bricks_count = game.resource::<BricksCount>();
+    assert_eq!((bricks_count.total, bricks_count.current), (2, 2));
+    assert_eq!(game.resource::<Score>().score, 0);
+    assert_eq!(game.resource::<PlatformLifes>().current, 5);
+}
+
+#[test]
+fn launched_ball_breaks_brick() {
+    let mut game = TestGame::new(
+        config(),
+        vec![level(vec![brick(100.0, 200.0), brick(20.0, 300.0)])],
+    );
+    game.start();
+    game.launch(Vec2::new(100.0, 200.0));
+
+    assert!(game.step_until(600, |game| game.resource::<Score>().score != 0));
+    assert_eq!(game.resource::<Score>().score, 1);
+    assert_eq!(game.resource::<BricksCount>().current, 1);
+    assert_eq!(game.count::<GameBrick>(), 1);
+    assert_eq!(game.game_state(), GameState::InGame);
+}
+
+#[test]
+fn losing_last_life_ends_game() {
+    let mut game = TestGame::new(
+        GameConfig {
+            platform_lifes: 1,
+            ..config()
+        },
+        vec![level(vec![brick(20.0, 300.0)])],
+    );
+    game.start();
+    game.launch(ABOVE_PLATFORM);
+    // Ball comes back down where the platform was
+    game.press(MOVE_RIGHT);
+
+    assert!(game.step_until(2000, |game| game.game_state() != GameState::InGame));
+    assert_eq!(game.game_state(), GameState::EndGame);
+    assert_eq!(game.ui_state(), UiState::EndGame);
+    assert_eq!(game.resource::<PlatformLifes>().current, 0);
+}
+
+#[test]
+fn clearing_levels_completes_campaign() {
+    let mut game = TestGame::new(
+        config(),
+        vec![
+            level(vec![brick(100.0, 200.0)]),
+            level(vec![brick(100.0, 250.0)]),
+        ],
+    );
+    game.start();
+    game.launch(ABOVE_PLATFORM);
+
+    assert!(game.step_until(600, |game| game.game_state() != GameState::InGame));
+    assert_eq!(game.game_state(), GameState::LevelComplete);
+    assert_eq!(game.ui_state(), UiState::LevelComplete);
+    assert_eq!(game.resource::<BricksCount>().current, 0);
+
+    game.set_state(GameState::InGame, UiState::InGame);
+    assert_eq!(game.resource::<CurrentLevel>().index, 1);
+    assert_eq!(game.resource::<BricksCount>().current, 1);
+    game.launch(ABOVE_PLATFORM);
+
+    assert!(game.step_until(600, |game| game.game_state() != GameState::InGame));
+    assert_eq!(game.game_state(), GameState::EndGame);
+    assert_eq!(game.ui_state(), UiState::Victory);
+    // A brick and the bonus for all lifes on each level
+    let life_bonus = config().life_bonus * config().platform_lifes;
+    assert_eq!(game.resource::<Score>().score, 2 * (1 + life_bonus));
+}
+
+#[test]
+fn missing_level_ends_run() {
+    let mut game = TestGame::new(config(), vec![level(vec![brick(100.0, 200.0)])]);
+    // Second level of the campaign never loads
+    let campaign = game.resource::<CampaignHandle>().0.clone();
+    game.app
+        .world_mut()
+        .resource_mut::<Assets<Campaign>>()
+        .get_mut(&campaign)
+        .unwrap()
+        .levels
+        .push(Handle::default());
+    game.start();
+    game.launch(ABOVE_PLATFORM);
+
+    assert!(game.step_until(600, |game| game.game_state() != GameState::InGame));
+    assert_eq!(game.game_state(), GameState::LevelComplete);
+
+    game.set_state(GameState::InGame, UiState::InGame);
+    game.step(1);
+    assert_eq!(game.game_state(), GameState::NotInGame);
+    assert_eq!(game.ui_state(), UiState::MainMenu);
+    assert!(game.app.world().contains_resource::<LevelError>());
+}
+
+#[test]
+fn exit_saves_run() {
+    let mut game = TestGame::new(
+        config(),
+        vec![level(vec![brick(100.0, 200.0), brick(150.0, 200.0)])],
+    );
+    game.start();
+    game.step(10);
+    assert_eq!(game.game_state(), GameState::InGame);
+    assert!(!RunSnapshot::exists(game.resource::<DataDir>()));
+
+    // Like the window being closed mid-run
+    game.app.world_mut().send_event(AppExit::Success);
+    game.step(1);
+
+    let snapshot = RunSnapshot::load(game.resource::<DataDir>()).expect("run is saved on exit");
+    assert_eq!(snapshot.level, 0);
+    assert_eq!(snapshot.bricks.len(), 2);
+    assert_eq!(
+        snapshot.bricks_count.current,
+        game.resource::<BricksCount>().current
+    );
+    assert_eq!(snapshot.balls.len(), 1);
+}
+
+#[test]
+fn combo_multiplies_hits() {
+    let mut explosive = brick(100.0, 200.0);
+    explosive.kind = BrickKind::Explosive { radius: 25.0 };
+    let mut game = TestGame::new(
+        GameConfig {
+            combo_hits_per_multiplier: 1,
+            ..config()
+        },
+        vec![level(vec![
+            explosive,
+            brick(75.0, 200.0),
+            brick(125.0, 200.0),
+            brick(20.0, 300.0),
+        ])],
+    );
+    game.start();
+    game.launch(ABOVE_PLATFORM);
+
+    assert!(game.step_until(600, |game| game.resource::<Score>().score != 0));
+    // Explosion hits the neighbours right after the explosive brick
+    assert_eq!(game.resource::<Combo>().hits, 3);
+    assert_eq!(game.resource::<Score>().score, 3 + 2 + 3);
+
+    // Ball falls back to the platform
+    assert!(game.step_until(600, |game| game.resource::<Combo>().hits == 0));
+    assert_eq!(game.resource::<BricksCount>().current, 1);
+}
+
+/// Rows of bricks of every kind that do not move
+fn replay_level() -> Vec<Level> {
+    let mut bricks = Vec::new();
+    for (row, y) in [150.0, 165.0, 180.0].into_iter().enumerate() {
+        for column in 0..8 {
+            let mut brick = brick(12.0 + 25.0 * column as f32, y);
+            match (row + column) % 4 {
+                1 => {
+                    brick.kind = BrickKind::MultiHit;
+                    brick.health = 2;
+                }
+                3 if row == 1 => brick.kind = BrickKind::Explosive { radius: 25.0 },
+                _ => {}
+            }
+            bricks.push(brick);
+        }
+    }
+    vec![level(bricks)]
+}
+
+#[test]
+fn replay_repeats_run() {
+    let config = GameConfig {
+        power_up_drop_chance: 0.5,
+        ..default()
+    };
+
+    let mut game = TestGame::new(config.clone(), replay_level());
+    game.start();
+    game.launch(Vec2::new(70.0, 300.0));
+    game.press(MOVE_RIGHT);
+    game.step(60);
+    game.release(MOVE_RIGHT);
+    game.step(200);
+    game.press(MOVE_LEFT);
+    game.step(90);
+    game.release(MOVE_LEFT);
+    game.step(400);
+    assert_eq!(game.game_state(), GameState::InGame);
+    assert_ne!(game.resource::<Score>().score, 0);
+
+    let score = game.resource::<Score>().score;
+    let replay = game
+        .resource::<ReplayRecorder>()
+        .replay(config.physics_tick_rate, score, 1);
+    let bricks = game.resource::<BricksCount>().current;
+    let lifes = game.resource::<PlatformLifes>().current;
+    let platform = game.positions::<GamePlatform>();
+    let mut balls = game.positions::<GameBall>();
+
+    let mut replayed = TestGame::new(config, replay_level());
+    replayed
+        .app
+        .insert_resource(ReplayPlayback::new(replay.clone()));
+    replayed.set_state(GameState::InGame, UiState::Replay);
+    assert!(replayed.step_until(replay.ticks() + 10, |game| {
+        game.resource::<ReplayPlayback>().finished()
+    }));
+    // Time stands still once the inputs are over
+    replayed.step(10);
+
+    assert_eq!(replayed.resource::<Score>().score, score);
+    assert_eq!(replayed.resource::<BricksCount>().current, bricks);
+    assert_eq!(replayed.resource::<PlatformLifes>().current, lifes);
+    assert_eq!(replayed.positions::<GamePlatform>(), platform);
+    let mut replayed_balls = replayed.positions::<GameBall>();
+    balls.sort_by(|a, b| a.x.total_cmp(&b.x));
+    replayed_balls.sort_by(|a, b| a.x.total_cmp(&b.x));
+    assert_eq!(replayed_balls, balls);
+}